@@ -0,0 +1,232 @@
+//! `#[derive(SheetRow)]` for the `googlesheets` crate.
+//!
+//! Use it through `googlesheets` with the `derive` feature enabled; see the `SheetRow` trait
+//! there for the attributes it understands.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr, Path, Result};
+
+#[proc_macro_derive(SheetRow, attributes(sheet))]
+pub fn derive_sheet_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// What a field's `#[sheet(...)]` attributes say about it.
+#[derive(Default)]
+struct FieldOptions {
+    column: Option<usize>,
+    header: Option<String>,
+    skip: bool,
+    with: Option<Path>,
+}
+
+/// A field that maps to a column.
+struct Column {
+    ident: Ident,
+    header: String,
+    index: usize,
+    with: Option<Path>,
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "SheetRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "SheetRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns = Vec::new();
+    let mut skipped = Vec::new();
+    let mut next_index = 0;
+    for field in fields {
+        let ident = field.ident.clone().expect("named fields have idents");
+        let options = field_options(field)?;
+        if options.skip {
+            skipped.push(ident);
+            continue;
+        }
+
+        let index = options.column.unwrap_or(next_index);
+        if let Some(taken) = columns.iter().find(|c: &&Column| c.index == index) {
+            return Err(Error::new_spanned(
+                field,
+                format!(
+                    "column {} is already used by `{}`",
+                    index_to_column(index),
+                    taken.ident
+                ),
+            ));
+        }
+        next_index = index + 1;
+
+        columns.push(Column {
+            header: options
+                .header
+                .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()),
+            ident,
+            index,
+            with: options.with,
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let column_defs = columns.iter().map(|column| {
+        let (header, index) = (&column.header, column.index);
+        quote! {
+            ::googlesheets::SheetColumn { header: #header, index: #index }
+        }
+    });
+
+    let to_cells = columns.iter().map(|column| {
+        let (ident, index) = (&column.ident, column.index);
+        match &column.with {
+            Some(with) => quote! { (#index, #with::to_cell(&self.#ident)) },
+            None => quote! { (#index, ::googlesheets::ToCell::to_cell(&self.#ident)) },
+        }
+    });
+
+    let from_cells = columns.iter().map(|column| {
+        let (ident, header, index) = (&column.ident, &column.header, column.index);
+        let convert = match &column.with {
+            Some(with) => quote! { #with::from_cell },
+            None => quote! { ::googlesheets::FromCell::from_cell },
+        };
+        quote! {
+            #ident: ::googlesheets::__private::read_cell(row, #index, #header, #convert)?
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::googlesheets::SheetRow for #name #type_generics #where_clause {
+            fn columns() -> ::std::vec::Vec<::googlesheets::SheetColumn> {
+                ::std::vec![#(#column_defs),*]
+            }
+
+            fn to_row(&self) -> ::std::vec::Vec<::googlesheets::CellValue> {
+                ::googlesheets::__private::build_row(::std::vec![#(#to_cells),*])
+            }
+
+            fn from_row(
+                row: &[::googlesheets::CellValue],
+            ) -> ::std::result::Result<Self, ::googlesheets::ApiError> {
+                ::std::result::Result::Ok(Self {
+                    #(#from_cells,)*
+                    #(#skipped: ::std::default::Default::default(),)*
+                })
+            }
+        }
+    })
+}
+
+/// Reads the `#[sheet(...)]` attributes on a field.
+fn field_options(field: &syn::Field) -> Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sheet"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("column") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let index = column_to_index(&lit.value()).ok_or_else(|| {
+                    Error::new(
+                        lit.span(),
+                        "expected a column letter, such as \"C\" or \"AB\"",
+                    )
+                })?;
+                options.column = Some(index);
+            } else if meta.path.is_ident("header") {
+                let lit: LitStr = meta.value()?.parse()?;
+                options.header = Some(lit.value());
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("with") {
+                let value = meta.value()?;
+                // both `with = "module"` and `with = module` are accepted
+                let path = if value.peek(LitStr) {
+                    value.parse::<LitStr>()?.parse()?
+                } else {
+                    value.parse()?
+                };
+                options.with = Some(path);
+            } else {
+                return Err(meta.error("expected `column`, `header`, `skip` or `with`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    if options.skip && (options.column.is_some() || options.header.is_some()) {
+        return Err(Error::new_spanned(
+            field,
+            "a skipped field can't also have a column or header",
+        ));
+    }
+    Ok(options)
+}
+
+/// The zero-indexed number of a column like `"A"` or `"AB"`.
+fn column_to_index(column: &str) -> Option<usize> {
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    column
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0usize, |acc, b| {
+            acc.checked_mul(26)?.checked_add((b - b'A' + 1) as usize)
+        })
+        .map(|n| n - 1)
+}
+
+/// The letters of a zero-indexed column, for error messages.
+fn index_to_column(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_to_index, index_to_column};
+
+    #[test]
+    fn test_column_letters() {
+        assert_eq!(column_to_index("A"), Some(0));
+        assert_eq!(column_to_index("c"), Some(2));
+        assert_eq!(column_to_index("AB"), Some(27));
+        assert_eq!(column_to_index(""), None);
+        assert_eq!(column_to_index("A1"), None);
+        for index in [0, 25, 26, 701, 702, 18277] {
+            assert_eq!(column_to_index(&index_to_column(index)), Some(index));
+        }
+    }
+}
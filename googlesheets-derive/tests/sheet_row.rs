@@ -0,0 +1,107 @@
+use googlesheets::{ApiError, CellValue, SheetColumn, SheetRow};
+
+mod due_formula {
+    use googlesheets::CellValue;
+
+    /// Days until due, written as a formula counting from today.
+    pub fn to_cell(days: &u32) -> CellValue {
+        CellValue::formula(&format!("=TODAY()+{}", days))
+    }
+
+    pub fn from_cell(cell: &CellValue) -> Result<u32, String> {
+        cell.as_str()
+            .and_then(|formula| formula.strip_prefix("=TODAY()+"))
+            .and_then(|days| days.parse().ok())
+            .ok_or_else(|| format!("expected =TODAY()+<days>, found {:?}", cell))
+    }
+}
+
+#[derive(Debug, PartialEq, SheetRow)]
+struct Task {
+    #[sheet(header = "Title")]
+    title: String,
+    done: bool,
+    #[sheet(column = "E", header = "Due", with = "due_formula")]
+    due_in: u32,
+    #[sheet(skip)]
+    cached: Vec<u8>,
+    estimate: Option<f64>,
+}
+
+fn task() -> Task {
+    Task {
+        title: String::from("Write docs"),
+        done: false,
+        due_in: 3,
+        cached: Vec::new(),
+        estimate: Some(1.5),
+    }
+}
+
+#[test]
+fn test_columns() {
+    assert_eq!(
+        Task::columns(),
+        vec![
+            SheetColumn {
+                header: "Title",
+                index: 0
+            },
+            SheetColumn {
+                header: "done",
+                index: 1
+            },
+            SheetColumn {
+                header: "Due",
+                index: 4
+            },
+            SheetColumn {
+                header: "estimate",
+                index: 5
+            },
+        ]
+    );
+    assert_eq!(
+        Task::header_row(),
+        vec![
+            CellValue::from("Title"),
+            CellValue::from("done"),
+            CellValue::Empty,
+            CellValue::Empty,
+            CellValue::from("Due"),
+            CellValue::from("estimate"),
+        ]
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let row = task().to_row();
+    assert_eq!(
+        row,
+        vec![
+            CellValue::from("Write docs"),
+            CellValue::Bool(false),
+            CellValue::Empty,
+            CellValue::Empty,
+            CellValue::formula("=TODAY()+3"),
+            CellValue::Number(1.5),
+        ]
+    );
+    assert_eq!(Task::from_row(&row).unwrap(), task());
+}
+
+#[test]
+fn test_from_row_errors_name_the_column() {
+    let mut row = task().to_row();
+    row[4] = CellValue::from("tomorrow");
+    match Task::from_row(&row) {
+        Err(ApiError::InvalidCell { column, .. }) => assert_eq!(column, "Due"),
+        other => panic!("unexpected result: {:?}", other.map_err(|e| e.to_string())),
+    }
+
+    // missing trailing cells read as empty
+    row.truncate(5);
+    row[4] = CellValue::formula("=TODAY()+1");
+    assert_eq!(Task::from_row(&row).unwrap().estimate, None);
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Error values a formula can evaluate to.
+const ERROR_VALUES: [&str; 9] = [
+    "#NULL!", "#DIV/0!", "#VALUE!", "#REF!", "#NAME?", "#NUM!", "#N/A", "#ERROR!", "#SPILL!",
+];
+
+/// The value of a single cell.
+///
+/// Cells are read from and written to the API as plain JSON strings, numbers and booleans.
+/// Strings are sorted into formulas (starting with `=`, as read with
+/// [`ValueRenderOption::Formula`](crate::ValueRenderOption::Formula)), error values such as
+/// `#N/A`, and ordinary text.
+///
+/// ```rust
+/// use googlesheets::CellValue;
+///
+/// let row: Vec<CellValue> = vec!["Widget".into(), 3.into(), 9.99.into(), true.into()];
+/// let total = CellValue::formula("=B2*C2");
+/// assert_eq!(total, CellValue::Formula(String::from("=B2*C2")));
+/// ```
+#[derive(Default, Clone, Debug, PartialEq)]
+pub enum CellValue {
+    /// A blank cell. Written as an empty string, which clears the cell.
+    #[default]
+    Empty,
+    /// Text. Written the same way as a formula, so with the default
+    /// [`ValueInputOption::UserEntered`](crate::ValueInputOption::UserEntered) text starting
+    /// with `=` is still evaluated, and text like `12.50` is stored as a number. Write with
+    /// [`WriteOptions::raw`](crate::WriteOptions::raw) to store it as-is.
+    String(String),
+    /// A number. `NaN` and infinities can't be written: the API would read them as `null`,
+    /// which leaves the cell unchanged, so serializing them fails instead.
+    Number(f64),
+    Bool(bool),
+    /// A formula, including the leading `=`.
+    Formula(String),
+    /// An error value, such as `#N/A` or `#DIV/0!`.
+    Error(String),
+}
+
+impl CellValue {
+    /// A formula cell. The leading `=` is added if it's missing.
+    pub fn formula(formula: &str) -> Self {
+        if formula.starts_with('=') {
+            CellValue::Formula(String::from(formula))
+        } else {
+            CellValue::Formula(format!("={}", formula))
+        }
+    }
+
+    /// Sorts a string read from the API into text, a formula or an error value.
+    fn from_api_string(s: String) -> Self {
+        if s.is_empty() {
+            CellValue::Empty
+        } else if s.starts_with('=') {
+            CellValue::Formula(s)
+        } else if ERROR_VALUES.contains(&s.as_str()) {
+            CellValue::Error(s)
+        } else {
+            CellValue::String(s)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, CellValue::Empty)
+    }
+
+    /// The text of a string, formula or error cell.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CellValue::String(s) | CellValue::Formula(s) | CellValue::Error(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            CellValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            CellValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+/// Formats the value the way it would be written to the API; `Empty` is an empty string.
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CellValue::Empty => Ok(()),
+            CellValue::String(s) | CellValue::Formula(s) | CellValue::Error(s) => f.write_str(s),
+            CellValue::Number(n) => write!(f, "{}", n),
+            CellValue::Bool(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+        }
+    }
+}
+
+impl Serialize for CellValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CellValue::Empty => serializer.serialize_str(""),
+            CellValue::String(s) | CellValue::Formula(s) | CellValue::Error(s) => {
+                serializer.serialize_str(s)
+            }
+            CellValue::Number(n) if n.is_finite() => serializer.serialize_f64(*n),
+            CellValue::Number(n) => Err(ser::Error::custom(format!(
+                "{} can't be written to a cell",
+                n
+            ))),
+            CellValue::Bool(b) => serializer.serialize_bool(*b),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CellValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CellValueVisitor;
+
+        impl<'de> Visitor<'de> for CellValueVisitor {
+            type Value = CellValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string, number, boolean or null")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<CellValue, E> {
+                Ok(CellValue::Empty)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<CellValue, E> {
+                Ok(CellValue::Empty)
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<CellValue, E> {
+                Ok(CellValue::Bool(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<CellValue, E> {
+                Ok(CellValue::Number(v as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<CellValue, E> {
+                Ok(CellValue::Number(v as f64))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<CellValue, E> {
+                Ok(CellValue::Number(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<CellValue, E> {
+                Ok(CellValue::from_api_string(String::from(v)))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<CellValue, E> {
+                Ok(CellValue::from_api_string(v))
+            }
+        }
+
+        deserializer.deserialize_any(CellValueVisitor)
+    }
+}
+
+impl From<String> for CellValue {
+    /// Text, or [`Empty`](CellValue::Empty) for an empty string. Use [`CellValue::formula`] for
+    /// formulas; text starting with `=` is evaluated like one unless written with
+    /// [`WriteOptions::raw`](crate::WriteOptions::raw).
+    fn from(s: String) -> Self {
+        if s.is_empty() {
+            CellValue::Empty
+        } else {
+            CellValue::String(s)
+        }
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        CellValue::from(String::from(s))
+    }
+}
+
+impl From<&String> for CellValue {
+    fn from(s: &String) -> Self {
+        CellValue::from(s.clone())
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(b: bool) -> Self {
+        CellValue::Bool(b)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for CellValue {
+                fn from(n: $t) -> Self {
+                    CellValue::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(f64, f32, i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+
+impl<T: Into<CellValue>> From<Option<T>> for CellValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(CellValue::Empty, Into::into)
+    }
+}
+
+/// Converts rows of anything that turns into a [`CellValue`].
+pub(crate) fn into_rows<T: Into<CellValue>>(rows: Vec<Vec<T>>) -> Vec<Vec<CellValue>> {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(Into::into).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::CellValue;
+
+    #[test]
+    fn test_deserialize_unformatted_values() {
+        let row: Vec<CellValue> =
+            serde_json::from_value(json!(["text", 1.5, 42, true, "", null])).unwrap();
+        assert_eq!(
+            row,
+            vec![
+                CellValue::String(String::from("text")),
+                CellValue::Number(1.5),
+                CellValue::Number(42.0),
+                CellValue::Bool(true),
+                CellValue::Empty,
+                CellValue::Empty,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_formulas_and_errors() {
+        let row: Vec<CellValue> =
+            serde_json::from_value(json!(["=SUM(A1:A3)", "#N/A", "#DIV/0!", "#hashtag"])).unwrap();
+        assert_eq!(
+            row,
+            vec![
+                CellValue::Formula(String::from("=SUM(A1:A3)")),
+                CellValue::Error(String::from("#N/A")),
+                CellValue::Error(String::from("#DIV/0!")),
+                CellValue::String(String::from("#hashtag")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let row = vec![
+            CellValue::Empty,
+            CellValue::from("text"),
+            CellValue::from(3),
+            CellValue::from(false),
+            CellValue::formula("A1+1"),
+        ];
+        let value = serde_json::to_value(&row).unwrap();
+        assert_eq!(value, json!(["", "text", 3.0, false, "=A1+1"]));
+        assert_eq!(
+            serde_json::from_value::<Vec<CellValue>>(value).unwrap(),
+            row
+        );
+    }
+
+    #[test]
+    fn test_serialize_rejects_non_finite_numbers() {
+        assert!(serde_json::to_value(CellValue::Number(f64::NAN)).is_err());
+        assert!(serde_json::to_value(vec![CellValue::Number(f64::INFINITY)]).is_err());
+    }
+
+    #[test]
+    fn test_from_impls() {
+        assert_eq!(CellValue::from(""), CellValue::Empty);
+        assert_eq!(CellValue::from(None::<i32>), CellValue::Empty);
+        assert_eq!(CellValue::from(Some(2u8)), CellValue::Number(2.0));
+        assert_eq!(
+            CellValue::from(String::from("=not a formula")).as_str(),
+            Some("=not a formula")
+        );
+        assert!(matches!(CellValue::from("=x"), CellValue::String(_)));
+        assert_eq!(CellValue::from(true).to_string(), "TRUE");
+        assert_eq!(CellValue::from(2.5).to_string(), "2.5");
+    }
+}
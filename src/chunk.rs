@@ -0,0 +1,545 @@
+use std::collections::BTreeMap;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::util::{get_a1_notation, parse_a1_notation, sheet_range, A1Range, LAST_COLUMN};
+use crate::{
+    BatchUpdateValuesRequest, BatchUpdateValuesResponse, CellValue, Dimension, Result, Sheets,
+    UpdateValuesResponse, ValueRange,
+};
+
+/// Limits on how much data a single write request carries, and how many run at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ChunkConfig {
+    pub max_cells: usize,
+    pub max_bytes: usize,
+    pub concurrency: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        // Google recommends keeping payloads under 2MB
+        Self {
+            max_cells: 100_000,
+            max_bytes: 2_000_000,
+            concurrency: 4,
+        }
+    }
+}
+
+/// A `ValueRange` along with its size, so it doesn't have to be measured twice.
+struct Chunk {
+    value_range: ValueRange,
+    cells: usize,
+    bytes: usize,
+}
+
+impl Chunk {
+    fn new(value_range: ValueRange) -> Self {
+        let rows = value_range.values.as_deref().unwrap_or_default();
+        Self {
+            cells: rows.iter().map(Vec::len).sum(),
+            bytes: rows.iter().map(|row| row_bytes(row)).sum::<usize>() + range_bytes(&value_range),
+            value_range,
+        }
+    }
+}
+
+/// Roughly how many bytes a row adds to a request body.
+fn row_bytes(row: &[CellValue]) -> usize {
+    serde_json::to_vec(row).map_or(0, |bytes| bytes.len()) + 1
+}
+
+/// Roughly how many bytes a `ValueRange` adds to a request body, besides its values.
+fn range_bytes(value_range: &ValueRange) -> usize {
+    value_range.range.as_ref().map_or(0, String::len) + 64
+}
+
+/// Splits a `ValueRange` into consecutive blocks of rows that each fit within `config`.
+///
+/// A range that already fits is returned untouched. Otherwise each block gets its own A1 range,
+/// anchored where the original range starts. Ranges that can't be split (column-major data,
+/// named ranges, a range without a starting cell, or rows reaching past column `ZZZ`) are
+/// returned whole.
+fn split_value_range(value_range: ValueRange, config: &ChunkConfig) -> Vec<Chunk> {
+    let whole = Chunk::new(value_range);
+    if whole.cells <= config.max_cells && whole.bytes <= config.max_bytes {
+        return vec![whole];
+    }
+    let value_range = whole.value_range;
+
+    let width = value_range
+        .values
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    let anchor = value_range
+        .range
+        .as_deref()
+        .and_then(parse_a1_notation)
+        .filter(|range| range.start_row.is_some())
+        .filter(|range| {
+            range
+                .start_column
+                .is_some_and(|column| column + width.max(1) - 1 <= LAST_COLUMN)
+        });
+
+    let anchor = match anchor {
+        Some(anchor) if value_range.major_dimension != Some(Dimension::COLUMNS) => anchor,
+        _ => return vec![Chunk::new(value_range)],
+    };
+
+    let overhead = range_bytes(&value_range);
+    let rows = value_range.values.unwrap_or_default();
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+    let mut block: Vec<Vec<CellValue>> = Vec::new();
+    let (mut cells, mut bytes) = (0, overhead);
+
+    for row in rows {
+        let (row_cells, row_size) = (row.len(), row_bytes(&row));
+        if !block.is_empty()
+            && (cells + row_cells > config.max_cells || bytes + row_size > config.max_bytes)
+        {
+            let len = block.len();
+            chunks.push(Chunk::new(block_range(&anchor, offset, block)));
+            offset += len;
+            block = Vec::new();
+            cells = 0;
+            bytes = overhead;
+        }
+        cells += row_cells;
+        bytes += row_size;
+        block.push(row);
+    }
+    if !block.is_empty() || chunks.is_empty() {
+        chunks.push(Chunk::new(block_range(&anchor, offset, block)));
+    }
+    chunks
+}
+
+/// A `ValueRange` for `rows`, starting `offset` rows below `anchor`.
+pub(crate) fn block_range(
+    anchor: &A1Range,
+    offset: usize,
+    rows: Vec<Vec<CellValue>>,
+) -> ValueRange {
+    let start_column = anchor.start_column.unwrap_or(0);
+    let start_row = anchor.start_row.unwrap_or(0) + offset;
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    let height = rows.len().max(1);
+
+    let a1 = get_a1_notation(
+        Some(start_column),
+        Some(start_row),
+        Some(start_column + width - 1),
+        Some(start_row + height - 1),
+    );
+    let range = match &anchor.sheet_title {
+        Some(title) => sheet_range(title, Some(&a1)),
+        None => a1,
+    };
+
+    ValueRange {
+        range: Some(range),
+        values: Some(rows),
+        major_dimension: Some(Dimension::ROWS),
+    }
+}
+
+/// Splits every range in a request and packs the pieces into requests that each fit within `config`.
+fn plan_requests(
+    request: &BatchUpdateValuesRequest,
+    config: &ChunkConfig,
+) -> Vec<BatchUpdateValuesRequest> {
+    let mut requests = Vec::new();
+    let mut data = Vec::new();
+    let (mut cells, mut bytes) = (0, 0);
+
+    let chunks = request
+        .data
+        .iter()
+        .cloned()
+        .flat_map(|value_range| split_value_range(value_range, config));
+
+    for chunk in chunks {
+        if !data.is_empty()
+            && (cells + chunk.cells > config.max_cells || bytes + chunk.bytes > config.max_bytes)
+        {
+            requests.push(BatchUpdateValuesRequest {
+                data: std::mem::take(&mut data),
+                ..request.clone_options()
+            });
+            cells = 0;
+            bytes = 0;
+        }
+        cells += chunk.cells;
+        bytes += chunk.bytes;
+        data.push(chunk.value_range);
+    }
+    if !data.is_empty() || requests.is_empty() {
+        requests.push(BatchUpdateValuesRequest {
+            data,
+            ..request.clone_options()
+        });
+    }
+    requests
+}
+
+/// Counts how many indexes a set of inclusive intervals covers, without counting overlaps twice.
+fn covered(mut intervals: Vec<(usize, usize)>) -> usize {
+    intervals.sort_unstable();
+    let mut total = 0;
+    let mut current: Option<(usize, usize)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((s, e)) if start <= e + 1 => Some((s, e.max(end))),
+            Some((s, e)) => {
+                total += e - s + 1;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map_or(0, |(s, e)| e - s + 1)
+}
+
+/// Combines the responses of a split request into the response the single request would have had.
+fn merge_batch_responses(responses: Vec<BatchUpdateValuesResponse>) -> BatchUpdateValuesResponse {
+    let responses: Vec<UpdateValuesResponse> = responses
+        .into_iter()
+        .flat_map(|response| response.responses)
+        .collect();
+
+    // rows and columns are counted once per sheet, even when several chunks touched them
+    let mut rows: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+    let mut columns: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+    let (mut extra_rows, mut extra_columns) = (0, 0);
+    for response in &responses {
+        let range = response
+            .updated_range
+            .as_deref()
+            .and_then(parse_a1_notation);
+        match range {
+            Some(A1Range {
+                sheet_title,
+                start_column: Some(start_column),
+                start_row: Some(start_row),
+                end_column: Some(end_column),
+                end_row: Some(end_row),
+            }) => {
+                let sheet = sheet_title.unwrap_or_default();
+                rows.entry(sheet.clone())
+                    .or_default()
+                    .push((start_row, end_row));
+                columns
+                    .entry(sheet)
+                    .or_default()
+                    .push((start_column, end_column));
+            }
+            _ => {
+                extra_rows += response.updated_rows.unwrap_or(0);
+                extra_columns += response.updated_columns.unwrap_or(0);
+            }
+        }
+    }
+
+    BatchUpdateValuesResponse {
+        spreadsheet_id: responses.iter().find_map(|r| r.spreadsheet_id.clone()),
+        total_updated_rows: Some(
+            rows.into_values().map(covered).sum::<usize>() as i32 + extra_rows,
+        ),
+        total_updated_columns: Some(
+            columns.values().cloned().map(covered).sum::<usize>() as i32 + extra_columns,
+        ),
+        total_updated_cells: Some(responses.iter().filter_map(|r| r.updated_cells).sum()),
+        total_updated_sheets: Some(columns.len().max(usize::from(!responses.is_empty())) as i32),
+        responses,
+    }
+}
+
+/// The range from the start of the first of `ranges` to the end of the last, as wide as the
+/// widest. Meant for consecutive blocks of rows on one sheet.
+pub(crate) fn span<'a>(ranges: impl Iterator<Item = &'a str>) -> Option<String> {
+    let ranges: Vec<A1Range> = ranges.map(parse_a1_notation).collect::<Option<_>>()?;
+    let (first, last) = (ranges.first()?, ranges.last()?);
+    let a1 = get_a1_notation(
+        first.start_column,
+        first.start_row,
+        ranges.iter().filter_map(|range| range.end_column).max(),
+        last.end_row,
+    );
+    Some(match &first.sheet_title {
+        Some(title) => sheet_range(title, Some(&a1)),
+        None => a1,
+    })
+}
+
+/// Folds the responses for the pieces of one split range back into a single response.
+fn merge_update_responses(responses: Vec<UpdateValuesResponse>) -> UpdateValuesResponse {
+    let updated_range = span(responses.iter().filter_map(|r| r.updated_range.as_deref()));
+
+    let updated_data = if responses.iter().all(|r| r.updated_data.is_some()) {
+        let values = responses
+            .iter()
+            .flat_map(|r| {
+                r.updated_data
+                    .as_ref()
+                    .and_then(|data| data.values.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        Some(ValueRange {
+            range: updated_range.clone(),
+            values: Some(values),
+            major_dimension: Some(Dimension::ROWS),
+        })
+    } else {
+        None
+    };
+
+    let totals = merge_batch_responses(vec![BatchUpdateValuesResponse {
+        responses: responses.clone(),
+        ..Default::default()
+    }]);
+
+    UpdateValuesResponse {
+        spreadsheet_id: totals.spreadsheet_id,
+        updated_columns: totals.total_updated_columns,
+        updated_range,
+        updated_rows: totals.total_updated_rows,
+        updated_data,
+        updated_cells: totals.total_updated_cells,
+    }
+}
+
+impl BatchUpdateValuesRequest {
+    /// A copy of everything but the data.
+    fn clone_options(&self) -> Self {
+        Self {
+            value_input_option: self.value_input_option,
+            data: Vec::new(),
+            include_values_in_response: self.include_values_in_response,
+            response_value_render_option: self.response_value_render_option,
+            response_date_time_render_option: self.response_date_time_render_option,
+        }
+    }
+}
+
+impl Sheets {
+    /// Sends a `values:batchUpdate`, splitting it into several requests if it's larger than the
+    /// client's limits. The pieces are sent with bounded concurrency and their responses merged.
+    pub(crate) async fn chunked_batch_update(
+        &self,
+        request: &BatchUpdateValuesRequest,
+    ) -> Result<BatchUpdateValuesResponse> {
+        let requests = plan_requests(request, &self.client.chunking());
+        self.send_planned(requests).await
+    }
+
+    /// Sends a `values.update` through a split `values:batchUpdate` if it's larger than the
+    /// client's limits. Returns `None`, without sending anything, if it fits in one request.
+    pub(crate) async fn chunked_update(
+        &self,
+        request: &BatchUpdateValuesRequest,
+    ) -> Result<Option<UpdateValuesResponse>> {
+        let requests = plan_requests(request, &self.client.chunking());
+        if requests.len() == 1 && requests[0].data.len() == 1 {
+            return Ok(None);
+        }
+
+        let response = self.send_planned(requests).await?;
+        Ok(Some(merge_update_responses(response.responses)))
+    }
+
+    async fn send_planned(
+        &self,
+        mut requests: Vec<BatchUpdateValuesRequest>,
+    ) -> Result<BatchUpdateValuesResponse> {
+        if requests.len() == 1 {
+            return self.send_batch_update(&requests.remove(0)).await;
+        }
+
+        let responses = stream::iter(requests.iter())
+            .map(|request| self.send_batch_update(request))
+            .buffered(self.client.chunking().concurrency.max(1))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(merge_batch_responses(responses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        covered, merge_batch_responses, merge_update_responses, plan_requests, split_value_range,
+        ChunkConfig,
+    };
+    use crate::{
+        BatchUpdateValuesRequest, BatchUpdateValuesResponse, UpdateValuesResponse, ValueRange,
+    };
+
+    fn config(max_cells: usize) -> ChunkConfig {
+        ChunkConfig {
+            max_cells,
+            max_bytes: usize::MAX,
+            concurrency: 2,
+        }
+    }
+
+    fn rows(height: usize, width: usize) -> Vec<Vec<usize>> {
+        (0..height)
+            .map(|r| (0..width).map(|c| r * width + c).collect())
+            .collect()
+    }
+
+    fn ranges(request: &BatchUpdateValuesRequest) -> Vec<&str> {
+        request
+            .data
+            .iter()
+            .map(|v| v.range.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_small_ranges_are_not_split() {
+        let chunks = split_value_range(ValueRange::new("'Q1 Sales'!B2", rows(3, 2)), &config(100));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0].value_range.range.as_deref(),
+            Some("'Q1 Sales'!B2")
+        );
+
+        // ranges that fit are sent exactly as given
+        let open_ended =
+            split_value_range(ValueRange::new("Sheet1!A1:Z", rows(1, 2)), &config(100));
+        assert_eq!(
+            open_ended[0].value_range.range.as_deref(),
+            Some("Sheet1!A1:Z")
+        );
+    }
+
+    #[test]
+    fn test_named_and_wide_ranges_are_not_split() {
+        let request =
+            BatchUpdateValuesRequest::new(vec![ValueRange::new("Sales2024", rows(10, 2))]);
+        let requests = plan_requests(&request, &config(4));
+        assert_eq!(requests.len(), 1);
+        assert_eq!(ranges(&requests[0]), vec!["Sales2024"]);
+
+        let wide = split_value_range(ValueRange::new("Log!ZZY1", rows(10, 3)), &config(4));
+        assert_eq!(wide.len(), 1);
+        assert_eq!(wide[0].value_range.range.as_deref(), Some("Log!ZZY1"));
+    }
+
+    #[test]
+    fn test_split_by_cells_keeps_anchor() {
+        let chunks = split_value_range(ValueRange::new("'Q1 Sales'!B2", rows(5, 2)), &config(4));
+        let ranges: Vec<_> = chunks
+            .iter()
+            .map(|c| c.value_range.range.clone().unwrap())
+            .collect();
+        assert_eq!(
+            ranges,
+            vec!["'Q1 Sales'!B2:C3", "'Q1 Sales'!B4:C5", "'Q1 Sales'!B6:C6"]
+        );
+        assert_eq!(
+            chunks[2].value_range.values.as_ref().unwrap()[0][0],
+            8.into()
+        );
+    }
+
+    #[test]
+    fn test_split_by_bytes() {
+        let config = ChunkConfig {
+            max_cells: usize::MAX,
+            max_bytes: 200,
+            concurrency: 1,
+        };
+        let long_rows = vec![vec!["x".repeat(60)]; 6];
+        let chunks = split_value_range(ValueRange::new("Log!A1", long_rows), &config);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.value_range.values.as_ref().unwrap().len() == 2));
+    }
+
+    #[test]
+    fn test_unanchored_ranges_are_not_split() {
+        let chunks = split_value_range(ValueRange::new("'Log'", rows(10, 2)), &config(4));
+        assert_eq!(chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_requests_packs_chunks() {
+        let request = BatchUpdateValuesRequest::new(vec![
+            ValueRange::new("A!A1", rows(2, 2)),
+            ValueRange::new("B!A1", rows(1, 2)),
+            ValueRange::new("C!A1", rows(3, 2)),
+        ]);
+        let requests = plan_requests(&request, &config(6));
+        assert_eq!(requests.len(), 2);
+        assert_eq!(ranges(&requests[0]), vec!["A!A1", "B!A1"]);
+        assert_eq!(ranges(&requests[1]), vec!["C!A1"]);
+
+        let single = plan_requests(&request, &config(100));
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].data.len(), 3);
+    }
+
+    #[test]
+    fn test_covered() {
+        assert_eq!(covered(vec![]), 0);
+        assert_eq!(covered(vec![(0, 1), (2, 3)]), 4);
+        assert_eq!(covered(vec![(5, 9), (0, 1), (1, 6)]), 10);
+    }
+
+    fn response(range: &str, rows: i32, columns: i32) -> UpdateValuesResponse {
+        UpdateValuesResponse {
+            spreadsheet_id: Some(String::from("abc123")),
+            updated_range: Some(String::from(range)),
+            updated_rows: Some(rows),
+            updated_columns: Some(columns),
+            updated_cells: Some(rows * columns),
+            updated_data: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_batch_responses() {
+        let merged = merge_batch_responses(vec![
+            BatchUpdateValuesResponse {
+                responses: vec![response("Data!B2:C3", 2, 2), response("Data!B4:C5", 2, 2)],
+                ..Default::default()
+            },
+            BatchUpdateValuesResponse {
+                responses: vec![response("Summary!A1:A1", 1, 1)],
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(merged.spreadsheet_id.as_deref(), Some("abc123"));
+        assert_eq!(merged.responses.len(), 3);
+        assert_eq!(merged.total_updated_cells, Some(9));
+        assert_eq!(merged.total_updated_rows, Some(5));
+        assert_eq!(merged.total_updated_columns, Some(3));
+        assert_eq!(merged.total_updated_sheets, Some(2));
+    }
+
+    #[test]
+    fn test_merge_update_responses() {
+        let merged = merge_update_responses(vec![
+            response("'Q1 Sales'!B2:C3", 2, 2),
+            response("'Q1 Sales'!B4:C5", 2, 2),
+            response("'Q1 Sales'!B6:B6", 1, 1),
+        ]);
+        assert_eq!(merged.updated_range.as_deref(), Some("'Q1 Sales'!B2:C6"));
+        assert_eq!(merged.updated_rows, Some(5));
+        assert_eq!(merged.updated_columns, Some(2));
+        assert_eq!(merged.updated_cells, Some(9));
+        assert!(merged.updated_data.is_none());
+    }
+}
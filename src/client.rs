@@ -0,0 +1,674 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use oauth::authenticator::DefaultAuthenticator;
+use oauth::{AccessToken, InstalledFlowAuthenticator, InstalledFlowReturnMethod};
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use reqwest::Certificate;
+use reqwest::{header, Client, Method, Proxy, Request, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+
+use crate::chunk::ChunkConfig;
+use crate::metrics::{RequestEvent, RequestObserver, Stats, StatsCollector};
+use crate::{
+    ApiError, AuthenticateError, ClientBuildFail, ParseError, RequestError, Result, Sheets,
+    SpreadsheetId, SpreadsheetLink, TokenError, BASE_ENDPOINT,
+};
+
+/// The OAuth scope needed to read and write spreadsheets.
+pub(crate) const SPREADSHEETS_SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+/// A client for the Google Sheets API that can be shared across many spreadsheets.
+///
+/// The client owns authentication, the underlying HTTP client, and the retry and rate-limit
+/// settings. Cloning it is cheap, so hand out clones freely and open a lightweight [`Sheets`]
+/// handle for each spreadsheet with [`SheetsClient::spreadsheet`].
+///
+/// ```no_run
+/// use googlesheets::SheetsClient;
+///
+/// # async fn run() -> Result<(), googlesheets::ApiError> {
+/// let client = SheetsClient::initialize().await?;
+/// let budget = client.open("budget-spreadsheet-id")?;
+/// let roster = client.open("https://docs.google.com/spreadsheets/d/roster-spreadsheet-id/edit#gid=0")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SheetsClient {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
+    auth: Auth,
+    http: Client,
+    retry: RetryConfig,
+    rate_limit: Option<RateLimiter>,
+    observers: Vec<Arc<dyn RequestObserver>>,
+    stats: Option<Arc<StatsCollector>>,
+    chunking: ChunkConfig,
+}
+
+/// Where the client gets its bearer token from.
+enum Auth {
+    /// A fixed token. Requests fail with [`ApiError::TokenExpired`] once it expires.
+    Token(AccessToken),
+    /// An authenticator that caches tokens and refreshes them once they've expired.
+    Authenticator(DefaultAuthenticator),
+}
+
+/// How failed requests are retried.
+#[derive(Clone, Copy, Debug)]
+struct RetryConfig {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(32),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Exponential backoff for the given (zero-indexed) retry, capped at `max_backoff`.
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+}
+
+/// Spaces requests out so that no more than a fixed number are sent per period.
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            interval: per / requests.max(1),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Waits until the next request is allowed to go out.
+    async fn acquire(&self) {
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |slot| slot.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(wait_until.into()).await;
+    }
+}
+
+impl SheetsClient {
+    /// Create a client that authenticates every request with the given token.
+    pub fn new(token: AccessToken) -> Result<Self> {
+        Self::builder().token(token).build()
+    }
+
+    /// Create a client by running the installed-application OAuth flow.
+    ///
+    /// The application secret is read from `client_secret.json` and tokens are cached in
+    /// `tokencache.json`. The client refreshes its token on its own once it expires.
+    pub async fn initialize() -> Result<Self> {
+        let auth = SheetsClient::authenticator().await?;
+        // fetch a token up front so that scope problems surface here rather than on first use
+        auth.token(&[SPREADSHEETS_SCOPE])
+            .await
+            .context(TokenError {
+                scope: String::from(SPREADSHEETS_SCOPE),
+            })?;
+        Self::builder().authenticator(auth).build()
+    }
+
+    /// Start configuring a client.
+    pub fn builder() -> SheetsClientBuilder {
+        SheetsClientBuilder::default()
+    }
+
+    /// Open a handle to the spreadsheet with the given id.
+    ///
+    /// Handles are lightweight; they share this client's authentication, HTTP client and limits.
+    pub fn spreadsheet(&self, sheet_id: SpreadsheetId) -> Sheets {
+        Sheets {
+            client: self.clone(),
+            sheet_id,
+            gid: None,
+        }
+    }
+
+    /// Open a handle from either a raw spreadsheet id or a link to the spreadsheet.
+    ///
+    /// If the link points at a specific tab (`#gid=123`), the handle remembers it.
+    pub fn open(&self, id_or_link: &str) -> Result<Sheets> {
+        let link: SpreadsheetLink = id_or_link.parse()?;
+
+        Ok(Sheets {
+            gid: link.gid,
+            ..self.spreadsheet(link.id)
+        })
+    }
+
+    /// Build an authenticator from `client_secret.json`, persisting tokens to `tokencache.json`.
+    pub(crate) async fn authenticator() -> Result<DefaultAuthenticator> {
+        // Read application secret from a file. Sometimes it's easier to compile it directly into the binary.
+        let secret = oauth::read_application_secret("client_secret.json")
+            .await
+            .context(AuthenticateError {
+                meta: "Failed to configure secret from 'client_secret.json'",
+            })?;
+
+        // All authentication tokens are persisted to a file named `tokencache.json`.
+        // The authenticator takes care of caching tokens to disk and refreshing tokens once they've expired.
+        InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::HTTPRedirect)
+            .persist_tokens_to_disk("tokencache.json")
+            .build()
+            .await
+            .context(AuthenticateError {
+                meta: "Failed to build auth from secret. Try deleting 'tokencache.json' and running again.",
+            })
+    }
+
+    /// Get a valid token, refreshing it through the authenticator if there is one.
+    async fn token(&self) -> Result<AccessToken> {
+        match &self.inner.auth {
+            Auth::Token(token) if token.is_expired() => Err(ApiError::TokenExpired),
+            Auth::Token(token) => Ok(token.clone()),
+            Auth::Authenticator(auth) => {
+                auth.token(&[SPREADSHEETS_SCOPE]).await.context(TokenError {
+                    scope: String::from(SPREADSHEETS_SCOPE),
+                })
+            }
+        }
+    }
+
+    /// Makes a request to the Google Sheets API
+    ///
+    /// # Arguments
+    ///
+    /// - `method`: The type of request to make (GET, POST, etc.)
+    /// - `path`: The segments of the path to the endpoint (for example: `["spreadsheets", spreadsheet_id, "values", "{range}:append"]`).
+    ///   Each segment is percent-encoded, so ids and ranges can be passed as they are.
+    /// - `body`: The body of the request
+    /// - `query_params`: The query parameters to add on to the request, in a list of tuples with `Vec<(parameter_name, parameter_value)>`
+    pub(crate) async fn request<T: Serialize>(
+        &self,
+        method: Method,
+        path: &[&str],
+        body: T,
+        query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<Request> {
+        let url = endpoint_url(path);
+
+        let token = self.token().await?;
+        let bearer_token =
+            header::HeaderValue::from_str(&format!("Bearer {}", token.as_str())).unwrap();
+
+        // Set the default headers.
+        let mut headers = header::HeaderMap::new();
+        headers.append(header::AUTHORIZATION, bearer_token);
+        headers.append(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+
+        let mut request_builder = self
+            .inner
+            .http
+            .request(method.clone(), url)
+            .headers(headers);
+
+        if let Some(val) = query_params {
+            request_builder = request_builder.query(&val);
+        }
+
+        if method != Method::GET && method != Method::DELETE {
+            request_builder = request_builder.json(&body);
+        }
+
+        request_builder.build().context(RequestError {})
+    }
+
+    /// Sends a request, retrying rate-limited and transient failures, and parses the response.
+    ///
+    /// Calls that aren't safe to repeat, such as appends, are only retried when the server can't
+    /// have acted on them: on 429 and on failed connections, never on timeouts or 5xx.
+    ///
+    /// `endpoint` and `spreadsheet_id` are used to describe the call to observers, and to tell
+    /// whether it's safe to repeat.
+    pub(crate) async fn execute<R: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        spreadsheet_id: &str,
+        request: Request,
+    ) -> Result<R> {
+        let retry = self.inner.retry;
+        let idempotent = is_idempotent(endpoint);
+        let started = Instant::now();
+        let request_size = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map_or(0, |bytes| bytes.len() as u64);
+        let mut attempt = 0;
+
+        let outcome = loop {
+            if let Some(limiter) = &self.inner.rate_limit {
+                limiter.acquire().await;
+            }
+
+            // requests with JSON bodies can always be cloned; fall back to a single attempt otherwise
+            let outcome = match request.try_clone() {
+                Some(req) => self.inner.http.execute(req).await,
+                None => break self.inner.http.execute(request).await,
+            };
+
+            let should_retry = match &outcome {
+                Ok(res) => is_retryable(res.status(), idempotent),
+                // a connection that was never made can't have changed anything
+                Err(err) => err.is_connect() || (idempotent && err.is_timeout()),
+            };
+
+            if !should_retry || attempt >= retry.max_retries {
+                break outcome;
+            }
+
+            tokio::time::sleep(retry.backoff(attempt)).await;
+            attempt += 1;
+        };
+
+        let status = outcome.as_ref().ok().map(|res| res.status());
+        let body = match outcome {
+            Ok(res) => res.bytes().await,
+            Err(err) => Err(err),
+        };
+
+        self.observe(&RequestEvent {
+            endpoint,
+            spreadsheet_id,
+            status,
+            latency: started.elapsed(),
+            bytes_sent: request_size * u64::from(attempt + 1),
+            bytes_received: body.as_ref().map_or(0, |bytes| bytes.len() as u64),
+            retries: attempt,
+        });
+
+        let body = body.context(RequestError {})?;
+        match status {
+            Some(StatusCode::OK) => serde_json::from_slice(&body).context(ParseError {}),
+            Some(status_code) => Err(ApiError::GoogleSheetsApi {
+                status_code,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }),
+            None => unreachable!("a body can only be read from a response"),
+        }
+    }
+
+    /// Reports a finished call to every registered observer.
+    fn observe(&self, event: &RequestEvent<'_>) {
+        if let Some(stats) = &self.inner.stats {
+            stats.on_request(event);
+        }
+        for observer in &self.inner.observers {
+            observer.on_request(event);
+        }
+    }
+
+    /// A snapshot of the built-in counters, if they were enabled with
+    /// [`SheetsClientBuilder::collect_stats`].
+    pub fn stats(&self) -> Option<Stats> {
+        self.inner.stats.as_ref().map(|stats| stats.snapshot())
+    }
+
+    /// The limits writes are split by.
+    pub(crate) fn chunking(&self) -> ChunkConfig {
+        self.inner.chunking
+    }
+}
+
+/// Builds the URL for an endpoint from its path segments, percent-encoding each one.
+///
+/// Ranges such as `'Q1 Sales'!A1:B2` or tab titles containing `#`, `?` or `/` would otherwise
+/// end up in the fragment or query, or split into extra path segments.
+fn endpoint_url(segments: &[&str]) -> Url {
+    let mut url = Url::parse(BASE_ENDPOINT).unwrap();
+    url.path_segments_mut()
+        .expect("base endpoint is a valid base URL")
+        .pop_if_empty()
+        .extend(segments);
+    url
+}
+
+/// Rate limiting and server hiccups are worth retrying; anything else will fail again.
+///
+/// A rate-limited request was turned away before it ran, but a 5xx may come after the server
+/// already applied the change, so those are only retried if repeating the call is harmless.
+fn is_retryable(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || (idempotent && status.is_server_error())
+}
+
+/// Whether sending a call twice leaves the spreadsheet as sending it once would. Appends add
+/// the rows again, and structural updates such as deleting rows would apply twice.
+fn is_idempotent(endpoint: &str) -> bool {
+    !matches!(
+        endpoint,
+        "spreadsheets.values.append" | "spreadsheets.batchUpdate"
+    )
+}
+
+/// Configures a [`SheetsClient`].
+///
+/// Either [`token`](SheetsClientBuilder::token) or
+/// [`authenticator`](SheetsClientBuilder::authenticator) must be set before building.
+#[derive(Default)]
+pub struct SheetsClientBuilder {
+    auth: Option<Auth>,
+    retry: RetryConfig,
+    rate_limit: Option<(u32, Duration)>,
+    observers: Vec<Arc<dyn RequestObserver>>,
+    collect_stats: bool,
+    chunking: ChunkConfig,
+    proxy: Option<ProxyConfig>,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    root_certificates: Vec<Vec<u8>>,
+}
+
+/// An HTTPS proxy that every request is sent through.
+struct ProxyConfig {
+    url: String,
+    credentials: Option<(String, String)>,
+}
+
+impl SheetsClientBuilder {
+    /// Authenticate every request with a fixed token.
+    pub fn token(mut self, token: AccessToken) -> Self {
+        self.auth = Some(Auth::Token(token));
+        self
+    }
+
+    /// Authenticate with an authenticator, which refreshes tokens once they've expired.
+    pub fn authenticator(mut self, auth: DefaultAuthenticator) -> Self {
+        self.auth = Some(Auth::Authenticator(auth));
+        self
+    }
+
+    /// How many times a rate-limited (429) or server-failed (5xx) request is retried.
+    ///
+    /// Appends and other calls that aren't safe to repeat are only retried on 429 and on failed
+    /// connections, so a timed-out or 5xx append is never written twice.
+    ///
+    /// Defaults to 3. Use 0 to disable retries.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// The delay before the first retry; each further retry doubles it, up to `max`.
+    ///
+    /// Defaults to 500ms, capped at 32s.
+    pub fn retry_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.retry.initial_backoff = initial;
+        self.retry.max_backoff = max;
+        self
+    }
+
+    /// Send at most `requests` requests per `per`, spacing them out evenly.
+    ///
+    /// The Google Sheets API allows 60 requests per minute per user by default,
+    /// so `rate_limit(60, Duration::from_secs(60))` stays within quota.
+    pub fn rate_limit(mut self, requests: u32, per: Duration) -> Self {
+        self.rate_limit = Some((requests, per));
+        self
+    }
+
+    /// Call `observer` after every request, for example to export metrics.
+    pub fn observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Keep in-process counters of every request, readable with [`SheetsClient::stats`].
+    pub fn collect_stats(mut self) -> Self {
+        self.collect_stats = true;
+        self
+    }
+
+    /// The most cells a single write request may carry; larger writes are split into several
+    /// requests.
+    ///
+    /// Defaults to 100,000.
+    pub fn max_cells_per_request(mut self, max_cells: usize) -> Self {
+        self.chunking.max_cells = max_cells;
+        self
+    }
+
+    /// The largest body, in bytes, a single write request may carry; larger writes are split
+    /// into several requests.
+    ///
+    /// Defaults to 2MB, the payload size Google recommends staying under.
+    pub fn max_bytes_per_request(mut self, max_bytes: usize) -> Self {
+        self.chunking.max_bytes = max_bytes;
+        self
+    }
+
+    /// How many requests of a split write are in flight at once.
+    ///
+    /// Defaults to 4. Use 1 to send them one after another.
+    pub fn write_concurrency(mut self, concurrency: usize) -> Self {
+        self.chunking.concurrency = concurrency;
+        self
+    }
+
+    /// Send every request through the proxy at `url`, for example `http://proxy.corp:8080`.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(ProxyConfig {
+            url: String::from(url),
+            credentials: None,
+        });
+        self
+    }
+
+    /// Send every request through the proxy at `url`, authenticating with basic auth.
+    pub fn proxy_with_credentials(mut self, url: &str, username: &str, password: &str) -> Self {
+        self.proxy = Some(ProxyConfig {
+            url: String::from(url),
+            credentials: Some((String::from(username), String::from(password))),
+        });
+        self
+    }
+
+    /// Trust an extra root certificate, given in PEM format, on top of the TLS backend's own
+    /// roots: the system's with `native-tls`, or the bundled Mozilla roots with `rustls-tls`.
+    ///
+    /// Needed when a proxy re-signs traffic with a private certificate authority. Only available
+    /// with a TLS feature enabled.
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// The TLS backend is picked by cargo feature: `native-tls` (the default) or `rustls-tls`.
+    /// Only disabling default features removes `native-tls`, and OpenSSL with it, from the build.
+    /// Fails if no auth was configured, or if the proxy URL or a root certificate is invalid.
+    pub fn build(self) -> Result<SheetsClient> {
+        let auth = self.auth.ok_or(ApiError::MissingAuth)?;
+
+        let mut http = Client::builder();
+
+        #[cfg(feature = "rustls-tls")]
+        {
+            http = http.use_rustls_tls();
+        }
+
+        if let Some(config) = self.proxy {
+            let mut proxy = Proxy::all(&config.url).context(ClientBuildFail {})?;
+            if let Some((username, password)) = &config.credentials {
+                proxy = proxy.basic_auth(username, password);
+            }
+            http = http.proxy(proxy);
+        }
+
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        for pem in &self.root_certificates {
+            let certificate = Certificate::from_pem(pem).context(ClientBuildFail {})?;
+            http = http.add_root_certificate(certificate);
+        }
+
+        let http = http.build().context(ClientBuildFail {})?;
+
+        Ok(SheetsClient {
+            inner: Arc::new(ClientInner {
+                auth,
+                http,
+                retry: self.retry,
+                rate_limit: self
+                    .rate_limit
+                    .map(|(requests, per)| RateLimiter::new(requests, per)),
+                observers: self.observers,
+                stats: self
+                    .collect_stats
+                    .then(|| Arc::new(StatsCollector::default())),
+                chunking: self.chunking,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use super::{endpoint_url, is_idempotent, is_retryable, RetryConfig, SheetsClient};
+
+    fn values_url(range: &str) -> String {
+        endpoint_url(&["spreadsheets", "abc123", "values", range]).to_string()
+    }
+
+    #[test]
+    fn test_endpoint_url_plain_range() {
+        assert_eq!(
+            values_url("Sheet1!A1:B2"),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/Sheet1!A1:B2"
+        );
+        assert_eq!(
+            endpoint_url(&["spreadsheets", "abc123", "values:batchUpdate"]).as_str(),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values:batchUpdate"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_url_spaces_and_quotes() {
+        assert_eq!(
+            values_url("'Q1 Sales'!A1:B2:append"),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'Q1%20Sales'!A1:B2:append"
+        );
+        assert_eq!(
+            values_url(r#"'Say "hi"'!A1"#),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'Say%20%22hi%22'!A1"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_url_reserved_characters() {
+        assert_eq!(
+            values_url("'Q1/Q2 #1?'!A1"),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'Q1%2FQ2%20%231%3F'!A1"
+        );
+        assert_eq!(
+            values_url("'100%'!A:A"),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'100%25'!A:A"
+        );
+        let url = endpoint_url(&["spreadsheets", "abc123", "values", "'a/b'!A1"]);
+        assert_eq!(url.path_segments().unwrap().count(), 5);
+        assert_eq!(url.query(), None);
+        assert_eq!(url.fragment(), None);
+    }
+
+    #[test]
+    fn test_endpoint_url_unicode() {
+        assert_eq!(
+            values_url("'Données ✓'!A1"),
+            "https://sheets.googleapis.com/v4/spreadsheets/abc123/values/'Donn%C3%A9es%20%E2%9C%93'!A1"
+        );
+    }
+
+    #[test]
+    fn test_client_is_shareable() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<SheetsClient>();
+    }
+
+    #[test]
+    fn test_build_rejects_bad_proxy_and_certificate() {
+        let token = || serde_json::from_str(r#"{"value": "token", "expires_at": null}"#).unwrap();
+
+        assert!(SheetsClient::builder()
+            .token(token())
+            .proxy_with_credentials("http://proxy.corp:8080", "user", "hunter2")
+            .build()
+            .is_ok());
+        assert!(SheetsClient::builder()
+            .token(token())
+            .proxy("not a url")
+            .build()
+            .is_err());
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        assert!(SheetsClient::builder()
+            .token(token())
+            .add_root_certificate("not a certificate")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_and_caps() {
+        let retry = RetryConfig {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(4),
+        };
+        assert_eq!(retry.backoff(0), Duration::from_millis(500));
+        assert_eq!(retry.backoff(1), Duration::from_secs(1));
+        assert_eq!(retry.backoff(3), Duration::from_secs(4));
+        assert_eq!(retry.backoff(40), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS, true));
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE, true));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST, true));
+        assert!(!is_retryable(StatusCode::NOT_FOUND, true));
+    }
+
+    #[test]
+    fn test_appends_only_retry_when_rate_limited() {
+        assert!(!is_idempotent("spreadsheets.values.append"));
+        assert!(!is_idempotent("spreadsheets.batchUpdate"));
+        assert!(is_idempotent("spreadsheets.values.update"));
+        assert!(is_idempotent("spreadsheets.values.batchUpdate"));
+
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS, false));
+        assert!(!is_retryable(StatusCode::SERVICE_UNAVAILABLE, false));
+        assert!(!is_retryable(StatusCode::INTERNAL_SERVER_ERROR, false));
+    }
+}
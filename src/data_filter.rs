@@ -0,0 +1,477 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CellValue, DateTimeRenderOption, Dimension, GetValuesOptions, Result, Sheets, ValueInputOption,
+    ValueRange, ValueRenderOption, WriteOptions,
+};
+
+/// Selects the data in a spreadsheet, either by position or by the developer metadata attached to it.
+///
+/// See [Google Sheets Docs: `DataFilter`]
+///
+/// [Google Sheets Docs: `DataFilter`]: https://developers.google.com/sheets/api/reference/rest/v4/DataFilter
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DataFilter {
+    /// Selects data that matches the specified A1 range.
+    #[serde(rename = "a1Range")]
+    A1Range(String),
+    /// Selects data that matches the range described by the `GridRange`.
+    #[serde(rename = "gridRange")]
+    GridRange(GridRange),
+    /// Selects data associated with the developer metadata matching the criteria described by the lookup.
+    #[serde(rename = "developerMetadataLookup")]
+    DeveloperMetadataLookup(DeveloperMetadataLookup),
+}
+
+/// A range on a sheet, as zero-based, half-open indexes. Missing indexes make the range unbounded on that side.
+///
+/// See [Google Sheets Docs: `GridRange`]
+///
+/// [Google Sheets Docs: `GridRange`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/other#GridRange
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GridRange {
+    /// The sheet this range is on (the `gid` of the tab).
+    #[serde(rename = "sheetId", skip_serializing_if = "Option::is_none")]
+    pub sheet_id: Option<i64>,
+    /// The start row (inclusive) of the range.
+    #[serde(rename = "startRowIndex", skip_serializing_if = "Option::is_none")]
+    pub start_row_index: Option<i64>,
+    /// The end row (exclusive) of the range.
+    #[serde(rename = "endRowIndex", skip_serializing_if = "Option::is_none")]
+    pub end_row_index: Option<i64>,
+    /// The start column (inclusive) of the range.
+    #[serde(rename = "startColumnIndex", skip_serializing_if = "Option::is_none")]
+    pub start_column_index: Option<i64>,
+    /// The end column (exclusive) of the range.
+    #[serde(rename = "endColumnIndex", skip_serializing_if = "Option::is_none")]
+    pub end_column_index: Option<i64>,
+}
+
+/// Criteria for finding developer metadata. Only metadata matching every set field is selected.
+///
+/// See [Google Sheets Docs: `DeveloperMetadataLookup`]
+///
+/// [Google Sheets Docs: `DeveloperMetadataLookup`]: https://developers.google.com/sheets/api/reference/rest/v4/DataFilter#DeveloperMetadataLookup
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeveloperMetadataLookup {
+    /// Limits the selected metadata to that which is associated with a location of this type.
+    #[serde(rename = "locationType", skip_serializing_if = "Option::is_none")]
+    pub location_type: Option<DeveloperMetadataLocationType>,
+    /// Limits the selected metadata to that associated with this location.
+    #[serde(rename = "metadataLocation", skip_serializing_if = "Option::is_none")]
+    pub metadata_location: Option<DeveloperMetadataLocation>,
+    /// How `metadata_location` is matched. Defaults to exact matching.
+    #[serde(
+        rename = "locationMatchingStrategy",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub location_matching_strategy: Option<DeveloperMetadataLocationMatchingStrategy>,
+    /// Limits the selected metadata to the one with this id.
+    #[serde(rename = "metadataId", skip_serializing_if = "Option::is_none")]
+    pub metadata_id: Option<i64>,
+    /// Limits the selected metadata to those with this key.
+    #[serde(rename = "metadataKey", skip_serializing_if = "Option::is_none")]
+    pub metadata_key: Option<String>,
+    /// Limits the selected metadata to those with this value.
+    #[serde(rename = "metadataValue", skip_serializing_if = "Option::is_none")]
+    pub metadata_value: Option<String>,
+    /// Limits the selected metadata to those with this visibility.
+    #[serde(rename = "visibility", skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<DeveloperMetadataVisibility>,
+}
+
+impl DeveloperMetadataLookup {
+    /// Look up metadata by key and value, the usual way of tagging rows.
+    pub fn key_value(key: &str, value: &str) -> Self {
+        Self {
+            metadata_key: Some(String::from(key)),
+            metadata_value: Some(String::from(value)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Where developer metadata is attached.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeveloperMetadataLocation {
+    /// The type of location this object represents. Only set in responses.
+    #[serde(rename = "locationType", skip_serializing_if = "Option::is_none")]
+    pub location_type: Option<DeveloperMetadataLocationType>,
+    /// True when metadata is associated with the entire spreadsheet.
+    #[serde(rename = "spreadsheet", skip_serializing_if = "Option::is_none")]
+    pub spreadsheet: Option<bool>,
+    /// The id of the sheet when metadata is associated with an entire sheet.
+    #[serde(rename = "sheetId", skip_serializing_if = "Option::is_none")]
+    pub sheet_id: Option<i64>,
+    /// The rows or columns when metadata is associated with a dimension.
+    #[serde(rename = "dimensionRange", skip_serializing_if = "Option::is_none")]
+    pub dimension_range: Option<DimensionRange>,
+}
+
+/// A range of rows or columns on a sheet, as zero-based, half-open indexes.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DimensionRange {
+    /// The sheet this range is on (the `gid` of the tab).
+    #[serde(rename = "sheetId", skip_serializing_if = "Option::is_none")]
+    pub sheet_id: Option<i64>,
+    #[serde(rename = "dimension", skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<Dimension>,
+    /// The start (inclusive) of the span. Unbounded if missing.
+    #[serde(rename = "startIndex", skip_serializing_if = "Option::is_none")]
+    pub start_index: Option<i64>,
+    /// The end (exclusive) of the span. Unbounded if missing.
+    #[serde(rename = "endIndex", skip_serializing_if = "Option::is_none")]
+    pub end_index: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeveloperMetadataLocationType {
+    /// Metadata associated with a row.
+    #[serde(rename = "ROW")]
+    Row,
+    /// Metadata associated with a column.
+    #[serde(rename = "COLUMN")]
+    Column,
+    /// Metadata associated with a whole sheet.
+    #[serde(rename = "SHEET")]
+    Sheet,
+    /// Metadata associated with the whole spreadsheet.
+    #[serde(rename = "SPREADSHEET")]
+    Spreadsheet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeveloperMetadataLocationMatchingStrategy {
+    /// Only metadata at exactly the given location matches.
+    #[serde(rename = "EXACT_LOCATION")]
+    ExactLocation,
+    /// Metadata at any location intersecting the given location matches.
+    #[serde(rename = "INTERSECTING_LOCATION")]
+    IntersectingLocation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeveloperMetadataVisibility {
+    /// Visible to any project with access to the document.
+    #[serde(rename = "DOCUMENT")]
+    Document,
+    /// Only visible to the project that created the metadata.
+    #[serde(rename = "PROJECT")]
+    Project,
+}
+
+/// Values to write to the range selected by a data filter.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DataFilterValueRange {
+    /// The data filter describing where the values should be written.
+    #[serde(rename = "dataFilter")]
+    pub data_filter: DataFilter,
+    /// The major dimension of the values. Defaults to rows.
+    #[serde(rename = "majorDimension", skip_serializing_if = "Option::is_none")]
+    pub major_dimension: Option<Dimension>,
+    /// The values to write.
+    pub values: Vec<Vec<CellValue>>,
+}
+
+/// A value range that was matched by one or more data filters.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct MatchedValueRange {
+    /// The values matched by the data filters.
+    #[serde(rename = "valueRange")]
+    pub value_range: Option<ValueRange>,
+    /// The data filters from the request that matched the range of values.
+    #[serde(rename = "dataFilters", default)]
+    pub data_filters: Vec<DataFilter>,
+}
+
+/// The response returned from reading values by data filter.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchGetValuesByDataFilterResponse {
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The matched values, one `MatchedValueRange` per range matched by the filters.
+    #[serde(rename = "valueRanges", default)]
+    pub value_ranges: Vec<MatchedValueRange>,
+}
+
+/// The result of writing one `DataFilterValueRange`.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateValuesByDataFilterResponse {
+    /// The range (in A1 notation) that updates were applied to.
+    #[serde(rename = "updatedRange")]
+    pub updated_range: Option<String>,
+    #[serde(rename = "updatedRows")]
+    pub updated_rows: Option<i32>,
+    #[serde(rename = "updatedColumns")]
+    pub updated_columns: Option<i32>,
+    #[serde(rename = "updatedCells")]
+    pub updated_cells: Option<i32>,
+    /// The data filter that selected the range that was updated.
+    #[serde(rename = "dataFilter")]
+    pub data_filter: Option<DataFilter>,
+    /// The values after updates were applied, if they were requested.
+    #[serde(rename = "updatedData")]
+    pub updated_data: Option<ValueRange>,
+}
+
+/// The response returned from writing values by data filter.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchUpdateValuesByDataFilterResponse {
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    #[serde(rename = "totalUpdatedRows")]
+    pub total_updated_rows: Option<i32>,
+    #[serde(rename = "totalUpdatedColumns")]
+    pub total_updated_columns: Option<i32>,
+    #[serde(rename = "totalUpdatedCells")]
+    pub total_updated_cells: Option<i32>,
+    #[serde(rename = "totalUpdatedSheets")]
+    pub total_updated_sheets: Option<i32>,
+    /// The response for each range updated.
+    #[serde(default)]
+    pub responses: Vec<UpdateValuesByDataFilterResponse>,
+}
+
+/// The response returned from clearing values by data filter.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchClearValuesByDataFilterResponse {
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The ranges (in A1 notation) that were cleared.
+    #[serde(rename = "clearedRanges", default)]
+    pub cleared_ranges: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BatchGetValuesByDataFilterRequest<'a> {
+    #[serde(rename = "dataFilters")]
+    data_filters: &'a [DataFilter],
+    #[serde(rename = "majorDimension", skip_serializing_if = "Option::is_none")]
+    major_dimension: Option<Dimension>,
+    #[serde(rename = "valueRenderOption", skip_serializing_if = "Option::is_none")]
+    value_render_option: Option<ValueRenderOption>,
+    #[serde(
+        rename = "dateTimeRenderOption",
+        skip_serializing_if = "Option::is_none"
+    )]
+    date_time_render_option: Option<DateTimeRenderOption>,
+}
+
+#[derive(Serialize)]
+struct BatchUpdateValuesByDataFilterRequest<'a> {
+    #[serde(rename = "valueInputOption")]
+    value_input_option: ValueInputOption,
+    data: &'a [DataFilterValueRange],
+    #[serde(
+        rename = "includeValuesInResponse",
+        skip_serializing_if = "Option::is_none"
+    )]
+    include_values_in_response: Option<bool>,
+    #[serde(
+        rename = "responseValueRenderOption",
+        skip_serializing_if = "Option::is_none"
+    )]
+    response_value_render_option: Option<ValueRenderOption>,
+    #[serde(
+        rename = "responseDateTimeRenderOption",
+        skip_serializing_if = "Option::is_none"
+    )]
+    response_date_time_render_option: Option<DateTimeRenderOption>,
+}
+
+#[derive(Serialize)]
+struct BatchClearValuesByDataFilterRequest<'a> {
+    #[serde(rename = "dataFilters")]
+    data_filters: &'a [DataFilter],
+}
+
+impl Sheets {
+    /// Returns the values in every range matched by the data filters.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.batchGetByDataFilter`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.batchGetByDataFilter`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchGetByDataFilter
+    pub async fn batch_get_by_data_filter(
+        &self,
+        data_filters: &[DataFilter],
+    ) -> Result<BatchGetValuesByDataFilterResponse> {
+        self.batch_get_by_data_filter_with(data_filters, &GetValuesOptions::default())
+            .await
+    }
+
+    /// Returns the values in every range matched by the data filters, read according to
+    /// `options`.
+    pub async fn batch_get_by_data_filter_with(
+        &self,
+        data_filters: &[DataFilter],
+        options: &GetValuesOptions,
+    ) -> Result<BatchGetValuesByDataFilterResponse> {
+        let request = self
+            .client
+            .request(
+                Method::POST,
+                &[
+                    "spreadsheets",
+                    self.sheet_id.as_str(),
+                    "values:batchGetByDataFilter",
+                ],
+                BatchGetValuesByDataFilterRequest {
+                    data_filters,
+                    major_dimension: options.major_dimension,
+                    value_render_option: options.value_render_option,
+                    date_time_render_option: options.date_time_render_option,
+                },
+                None,
+            )
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchGetByDataFilter",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+
+    /// Writes values into the ranges matched by each data filter.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.batchUpdateByDataFilter`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.batchUpdateByDataFilter`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchUpdateByDataFilter
+    pub async fn batch_update_by_data_filter(
+        &self,
+        data: &[DataFilterValueRange],
+    ) -> Result<BatchUpdateValuesByDataFilterResponse> {
+        self.batch_update_by_data_filter_with(data, &WriteOptions::default())
+            .await
+    }
+
+    /// Writes values into the ranges matched by each data filter, according to `options`.
+    ///
+    /// `options.insert_data_option` is ignored; it only applies to appends.
+    pub async fn batch_update_by_data_filter_with(
+        &self,
+        data: &[DataFilterValueRange],
+        options: &WriteOptions,
+    ) -> Result<BatchUpdateValuesByDataFilterResponse> {
+        let request = self
+            .client
+            .request(
+                Method::POST,
+                &[
+                    "spreadsheets",
+                    self.sheet_id.as_str(),
+                    "values:batchUpdateByDataFilter",
+                ],
+                BatchUpdateValuesByDataFilterRequest {
+                    value_input_option: options.value_input_option,
+                    data,
+                    include_values_in_response: options.include_values_in_response,
+                    response_value_render_option: options.response_value_render_option,
+                    response_date_time_render_option: options.response_date_time_render_option,
+                },
+                None,
+            )
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchUpdateByDataFilter",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+
+    /// Clears the values in every range matched by the data filters, keeping formatting.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.batchClearByDataFilter`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.batchClearByDataFilter`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchClearByDataFilter
+    pub async fn batch_clear_by_data_filter(
+        &self,
+        data_filters: &[DataFilter],
+    ) -> Result<BatchClearValuesByDataFilterResponse> {
+        let request = self
+            .client
+            .request(
+                Method::POST,
+                &[
+                    "spreadsheets",
+                    self.sheet_id.as_str(),
+                    "values:batchClearByDataFilter",
+                ],
+                BatchClearValuesByDataFilterRequest { data_filters },
+                None,
+            )
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchClearByDataFilter",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        BatchGetValuesByDataFilterResponse, DataFilter, DeveloperMetadataLookup, GridRange,
+    };
+
+    #[test]
+    fn test_data_filter_serializes_as_union_field() {
+        assert_eq!(
+            serde_json::to_value(DataFilter::A1Range(String::from("Sheet1!A1:B2"))).unwrap(),
+            json!({"a1Range": "Sheet1!A1:B2"})
+        );
+        assert_eq!(
+            serde_json::to_value(DataFilter::GridRange(GridRange {
+                sheet_id: Some(0),
+                start_row_index: Some(1),
+                end_row_index: Some(5),
+                ..Default::default()
+            }))
+            .unwrap(),
+            json!({"gridRange": {"sheetId": 0, "startRowIndex": 1, "endRowIndex": 5}})
+        );
+        assert_eq!(
+            serde_json::to_value(DataFilter::DeveloperMetadataLookup(
+                DeveloperMetadataLookup::key_value("row-id", "42")
+            ))
+            .unwrap(),
+            json!({"developerMetadataLookup": {"metadataKey": "row-id", "metadataValue": "42"}})
+        );
+    }
+
+    #[test]
+    fn test_matched_value_ranges_deserialize() {
+        let response: BatchGetValuesByDataFilterResponse = serde_json::from_value(json!({
+            "spreadsheetId": "abc123",
+            "valueRanges": [{
+                "valueRange": {"range": "Sheet1!A2:C2", "majorDimension": "ROWS", "values": [["a", "b", "c"]]},
+                "dataFilters": [{"developerMetadataLookup": {"metadataKey": "row-id", "metadataValue": "42"}}]
+            }]
+        }))
+        .unwrap();
+
+        let matched = &response.value_ranges[0];
+        assert_eq!(
+            matched.value_range.as_ref().unwrap().range.as_deref(),
+            Some("Sheet1!A2:C2")
+        );
+        assert_eq!(
+            matched.data_filters,
+            vec![DataFilter::DeveloperMetadataLookup(
+                DeveloperMetadataLookup::key_value("row-id", "42")
+            )]
+        );
+    }
+}
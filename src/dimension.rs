@@ -0,0 +1,378 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{get_a1_notation, sheet_range};
+use crate::{
+    ApiError, CellValue, Dimension, DimensionRange, EmptyBody, GetValuesOptions, Result, Sheets,
+    Spreadsheet,
+};
+
+/// Properties of one sheet (tab) of a spreadsheet.
+///
+/// See [Google Sheets Docs: `SheetProperties`]
+///
+/// [Google Sheets Docs: `SheetProperties`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/sheets#sheetproperties
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SheetProperties {
+    /// The id of the sheet, shown as `gid` in links to it.
+    #[serde(rename = "sheetId")]
+    pub sheet_id: Option<i64>,
+    pub title: Option<String>,
+    /// The position of the sheet among the tabs, from 0.
+    pub index: Option<i64>,
+    /// The size of the sheet's grid. Only grid sheets have one; charts on their own tab don't.
+    #[serde(rename = "gridProperties")]
+    pub grid_properties: Option<GridProperties>,
+}
+
+/// The size of a sheet's grid. Ranges of the Values API can't reach past it.
+///
+/// See [Google Sheets Docs: `GridProperties`]
+///
+/// [Google Sheets Docs: `GridProperties`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/sheets#gridproperties
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct GridProperties {
+    #[serde(rename = "rowCount")]
+    pub row_count: Option<i64>,
+    #[serde(rename = "columnCount")]
+    pub column_count: Option<i64>,
+}
+
+/// How many rows and columns a sheet's grid has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct GridSize {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl GridSize {
+    /// Every cell of the grid from the zero-indexed `row` down, or `None` if the grid ends above it.
+    pub(crate) fn rows_from(&self, sheet_title: &str, row: usize) -> Option<String> {
+        if row >= self.rows || self.columns == 0 {
+            return None;
+        }
+        let a1 = get_a1_notation(
+            Some(0),
+            Some(row),
+            Some(self.columns - 1),
+            Some(self.rows - 1),
+        );
+        Some(sheet_range(sheet_title, Some(&a1)))
+    }
+}
+
+/// One change in a `spreadsheets.batchUpdate`.
+#[derive(Serialize)]
+enum Request {
+    #[serde(rename = "deleteDimension")]
+    DeleteDimension { range: DimensionRange },
+}
+
+#[derive(Serialize)]
+struct BatchUpdateSpreadsheetRequest {
+    requests: Vec<Request>,
+}
+
+#[derive(Deserialize)]
+struct BatchUpdateSpreadsheetResponse {}
+
+/// Groups zero-indexed rows into half-open runs of consecutive rows, last run first, so
+/// deleting them in order never shifts a row that's still to be deleted.
+fn bottom_up_runs(rows: &[usize]) -> Vec<(usize, usize)> {
+    let mut rows = rows.to_vec();
+    rows.sort_unstable();
+    rows.dedup();
+
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for row in rows {
+        match runs.last_mut() {
+            Some((_, end)) if *end == row => *end += 1,
+            _ => runs.push((row, row + 1)),
+        }
+    }
+    runs.reverse();
+    runs
+}
+
+/// Whether deleting `rows` would leave a grid of `grid_rows` rows without any.
+fn deletes_every_row(rows: &[usize], grid_rows: usize) -> bool {
+    let mut rows: Vec<usize> = rows
+        .iter()
+        .copied()
+        .filter(|&row| row < grid_rows)
+        .collect();
+    rows.sort_unstable();
+    rows.dedup();
+    grid_rows > 0 && rows.len() == grid_rows
+}
+
+/// The zero-indexed rows the predicate picks, in order.
+fn matching_rows(rows: &[Vec<CellValue>], predicate: impl Fn(&[CellValue]) -> bool) -> Vec<usize> {
+    rows.iter()
+        .enumerate()
+        .filter(|(_, row)| predicate(row))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+impl Sheets {
+    /// Returns the properties of every sheet (tab) in the spreadsheet, in tab order.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.get`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.get`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/get
+    pub async fn sheet_properties(&self) -> Result<Vec<SheetProperties>> {
+        let request = self
+            .client
+            .request(
+                Method::GET,
+                &["spreadsheets", self.sheet_id.as_str()],
+                EmptyBody {},
+                Some(vec![(
+                    "fields",
+                    "sheets.properties(sheetId,title,index,gridProperties(rowCount,columnCount))",
+                )]),
+            )
+            .await?;
+
+        let spreadsheet: Spreadsheet = self
+            .client
+            .execute("spreadsheets.get", self.sheet_id.as_str(), request)
+            .await?;
+        Ok(spreadsheet
+            .sheets
+            .into_iter()
+            .map(|sheet| sheet.properties)
+            .collect())
+    }
+
+    /// The properties of the sheet (tab) titled `sheet_title`.
+    async fn properties_of(&self, sheet_title: &str) -> Result<SheetProperties> {
+        self.sheet_properties()
+            .await?
+            .into_iter()
+            .find(|sheet| sheet.title.as_deref() == Some(sheet_title))
+            .ok_or_else(|| ApiError::SheetNotFound {
+                title: String::from(sheet_title),
+            })
+    }
+
+    /// The size of the grid of the sheet (tab) titled `sheet_title`, for keeping ranges inside
+    /// it. A sheet without a grid has a size of zero.
+    pub(crate) async fn grid_size(&self, sheet_title: &str) -> Result<GridSize> {
+        let grid = self
+            .properties_of(sheet_title)
+            .await?
+            .grid_properties
+            .unwrap_or_default();
+        Ok(GridSize {
+            rows: grid.row_count.unwrap_or(0).max(0) as usize,
+            columns: grid.column_count.unwrap_or(0).max(0) as usize,
+        })
+    }
+
+    /// Deletes whole rows from a sheet (tab), given as zero-indexed row numbers, in one
+    /// `spreadsheets.batchUpdate`. Rows below move up to fill the gaps.
+    ///
+    /// Fails with [`ApiError::DeleteAllRows`] before sending anything if the rows are all the
+    /// rows of the sheet's grid, which the API refuses to delete.
+    pub(crate) async fn delete_rows(&self, sheet_title: &str, rows: &[usize]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let sheet = self.properties_of(sheet_title).await?;
+        let sheet_id = sheet.sheet_id.ok_or_else(|| ApiError::SheetNotFound {
+            title: String::from(sheet_title),
+        })?;
+        let grid_rows = sheet
+            .grid_properties
+            .and_then(|grid| grid.row_count)
+            .unwrap_or(0)
+            .max(0) as usize;
+        if deletes_every_row(rows, grid_rows) {
+            return Err(ApiError::DeleteAllRows {
+                title: String::from(sheet_title),
+            });
+        }
+
+        let requests = bottom_up_runs(rows)
+            .into_iter()
+            .map(|(start, end)| Request::DeleteDimension {
+                range: DimensionRange {
+                    sheet_id: Some(sheet_id),
+                    dimension: Some(Dimension::ROWS),
+                    start_index: Some(start as i64),
+                    end_index: Some(end as i64),
+                },
+            })
+            .collect();
+
+        let request = self
+            .client
+            .request(
+                Method::POST,
+                &["spreadsheets", &format!("{}:batchUpdate", self.sheet_id)],
+                BatchUpdateSpreadsheetRequest { requests },
+                None,
+            )
+            .await?;
+
+        let _: BatchUpdateSpreadsheetResponse = self
+            .client
+            .execute("spreadsheets.batchUpdate", self.sheet_id.as_str(), request)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes the rows of a sheet (tab) that `predicate` picks, and returns them in order.
+    ///
+    /// Unlike [`clear_sheet`](Sheets::clear_sheet), the rows are removed rather than blanked:
+    /// rows below move up to close the gaps. The predicate sees every row as read with the
+    /// default formatting, headers included; blank rows between others are empty slices, and
+    /// trailing empty cells are left off.
+    ///
+    /// The rows are deleted from the bottom up in one `spreadsheets.batchUpdate`, so nothing
+    /// is sent if no row matches. A sheet can't be left without rows: if every row of its grid
+    /// matches, this fails with [`ApiError::DeleteAllRows`] and nothing is deleted.
+    ///
+    /// See [Google Sheets Docs: `DeleteDimensionRequest`]
+    ///
+    /// [Google Sheets Docs: `DeleteDimensionRequest`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/request#deletedimensionrequest
+    ///
+    /// ```no_run
+    /// use googlesheets::{CellValue, Sheets};
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// // drop every order marked as cancelled in column C
+    /// let deleted = sheets
+    ///     .delete_rows_where("Orders", |row| {
+    ///         row.get(2) == Some(&CellValue::from("cancelled"))
+    ///     })
+    ///     .await?;
+    /// println!("deleted {} rows", deleted.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_rows_where(
+        &self,
+        sheet_title: &str,
+        predicate: impl Fn(&[CellValue]) -> bool,
+    ) -> Result<Vec<Vec<CellValue>>> {
+        let get = GetValuesOptions {
+            major_dimension: Some(Dimension::ROWS),
+            ..Default::default()
+        };
+        let mut rows = self
+            .get_values_with(&sheet_range(sheet_title, None), &get)
+            .await?
+            .values
+            .unwrap_or_default();
+
+        let matches = matching_rows(&rows, predicate);
+        self.delete_rows(sheet_title, &matches).await?;
+
+        Ok(matches
+            .into_iter()
+            .map(|index| std::mem::take(&mut rows[index]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        bottom_up_runs, deletes_every_row, matching_rows, BatchUpdateSpreadsheetRequest, GridSize,
+        Request,
+    };
+    use crate::{CellValue, Dimension, DimensionRange};
+
+    #[test]
+    fn test_bottom_up_runs() {
+        assert_eq!(bottom_up_runs(&[]), vec![]);
+        assert_eq!(bottom_up_runs(&[4, 1, 2, 9, 3, 2]), vec![(9, 10), (1, 5)]);
+    }
+
+    #[test]
+    fn test_rows_from() {
+        let grid = GridSize {
+            rows: 1000,
+            columns: 26,
+        };
+        assert_eq!(
+            grid.rows_from("Tasks", 3).as_deref(),
+            Some("'Tasks'!A4:Z1000")
+        );
+        assert_eq!(grid.rows_from("Tasks", 1000), None);
+        assert_eq!(
+            GridSize {
+                rows: 5,
+                columns: 0
+            }
+            .rows_from("Tasks", 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_serialize_delete_dimension() {
+        let request = BatchUpdateSpreadsheetRequest {
+            requests: vec![Request::DeleteDimension {
+                range: DimensionRange {
+                    sheet_id: Some(7),
+                    dimension: Some(Dimension::ROWS),
+                    start_index: Some(1),
+                    end_index: Some(3),
+                },
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "requests": [{
+                    "deleteDimension": {
+                        "range": {"sheetId": 7, "dimension": "ROWS", "startIndex": 1, "endIndex": 3}
+                    }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_matching_rows() {
+        let rows: Vec<Vec<CellValue>> = serde_json::from_value(json!([
+            ["Order", "Status"],
+            [1, "shipped"],
+            [],
+            [2, "cancelled"],
+            [3, "cancelled"],
+        ]))
+        .unwrap();
+
+        let cancelled = CellValue::from("cancelled");
+        assert_eq!(
+            matching_rows(&rows, |row| row.get(1) == Some(&cancelled)),
+            vec![3, 4]
+        );
+        assert_eq!(matching_rows(&rows, |row| row.is_empty()), vec![2]);
+        assert_eq!(
+            bottom_up_runs(&matching_rows(&rows, |row| row.len() < 2)),
+            vec![(2, 3)]
+        );
+    }
+
+    #[test]
+    fn test_deletes_every_row() {
+        let rows: Vec<Vec<CellValue>> =
+            serde_json::from_value(json!([["Order"], [1], [2]])).unwrap();
+
+        // the data fills the grid, so matching every row would empty the sheet
+        let all = matching_rows(&rows, |_| true);
+        assert!(deletes_every_row(&all, 3));
+        // with empty rows below the data, some rows are left
+        assert!(!deletes_every_row(&all, 1000));
+        assert!(!deletes_every_row(&[0, 2, 2], 3));
+        assert!(!deletes_every_row(&[], 0));
+    }
+}
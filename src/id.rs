@@ -0,0 +1,293 @@
+use std::fmt;
+use std::str::FromStr;
+
+use reqwest::Url;
+
+use crate::{ApiError, Result};
+
+/// Where spreadsheets live in the Google Sheets web app.
+const DOCS_ENDPOINT: &str = "https://docs.google.com/spreadsheets/d/";
+
+/// The id of a spreadsheet, for example `1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms`.
+///
+/// Parse one from either a raw id or a link copied out of the browser:
+///
+/// ```rust
+/// use googlesheets::SpreadsheetId;
+///
+/// let from_id: SpreadsheetId = "1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms".parse().unwrap();
+/// let from_link: SpreadsheetId =
+///     "https://docs.google.com/spreadsheets/u/0/d/1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms/edit#gid=0"
+///         .parse()
+///         .unwrap();
+/// assert_eq!(from_id, from_link);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpreadsheetId(String);
+
+impl SpreadsheetId {
+    /// Validate a raw spreadsheet id. Ids are made of letters, digits, `-` and `_`.
+    pub fn new(id: &str) -> Result<Self> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if valid {
+            Ok(Self(String::from(id)))
+        } else {
+            Err(ApiError::InvalidSpreadsheetId {
+                input: String::from(id),
+            })
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Link to the spreadsheet, opening on its first tab.
+    pub fn link(&self) -> String {
+        format!("{}{}/", DOCS_ENDPOINT, self.0)
+    }
+
+    /// Link to a specific tab, and optionally a range within it, such as `A1:C10`.
+    pub fn link_to(&self, gid: i64, range: Option<&str>) -> String {
+        match range {
+            Some(range) => self.edit_link(&format!("gid={}&range={}", gid, range)),
+            None => self.edit_link(&format!("gid={}", gid)),
+        }
+    }
+
+    /// Link to a range, such as `A1:C10`, on the tab the spreadsheet opens on.
+    pub fn link_to_range(&self, range: &str) -> String {
+        self.edit_link(&format!("range={}", range))
+    }
+
+    /// Link to the spreadsheet's editor, with `fragment` after the `#`.
+    fn edit_link(&self, fragment: &str) -> String {
+        let mut url = Url::parse(DOCS_ENDPOINT).unwrap();
+        url.path_segments_mut()
+            .expect("docs endpoint is a valid base URL")
+            .pop_if_empty()
+            .extend(&[self.as_str(), "edit"]);
+        url.set_fragment(Some(fragment));
+        url.to_string()
+    }
+}
+
+impl FromStr for SpreadsheetId {
+    type Err = ApiError;
+
+    /// Accepts a raw id or any link to the spreadsheet.
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(s.parse::<SpreadsheetLink>()?.id)
+    }
+}
+
+impl fmt::Display for SpreadsheetId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for SpreadsheetId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A link to a spreadsheet, and to one of its tabs if the link named one.
+///
+/// Understands the links the web app hands out, including `/u/0/` account variants,
+/// Google Workspace `/a/<domain>/` links and the `gid` of the selected tab. Links to other hosts
+/// than `docs.google.com` are rejected, as are "Publish to the web" links
+/// (`/spreadsheets/d/e/2PACX-…`), which hide the spreadsheet's id:
+///
+/// ```rust
+/// use googlesheets::SpreadsheetLink;
+///
+/// let link: SpreadsheetLink = "https://docs.google.com/spreadsheets/d/abc123/edit#gid=123"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(link.id.as_str(), "abc123");
+/// assert_eq!(link.gid, Some(123));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpreadsheetLink {
+    pub id: SpreadsheetId,
+    /// The id of the tab the link points at, if any.
+    pub gid: Option<i64>,
+}
+
+impl FromStr for SpreadsheetLink {
+    type Err = ApiError;
+
+    /// Accepts any link to the spreadsheet, or a raw id for a link without a tab.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || ApiError::InvalidSpreadsheetId {
+            input: String::from(s),
+        };
+
+        let trimmed = s.trim();
+        if !trimmed.contains('/') {
+            return Ok(Self {
+                id: SpreadsheetId::new(trimmed)?,
+                gid: None,
+            });
+        }
+
+        // links are often pasted without a scheme
+        let url = Url::parse(trimmed)
+            .or_else(|_| Url::parse(&format!("https://{}", trimmed)))
+            .map_err(|_| invalid())?;
+        if url.host_str() != Some("docs.google.com") {
+            return Err(invalid());
+        }
+
+        // the id follows `/spreadsheets/.../d/`, which may have `/u/0/` or similar in between
+        let mut segments = url
+            .path_segments()
+            .ok_or_else(invalid)?
+            .skip_while(|segment| *segment != "spreadsheets")
+            .skip_while(|segment| *segment != "d")
+            .skip(1);
+        let id = match segments.next() {
+            // published links carry a token in place of the id, and the API can't use it
+            Some("e") | None => return Err(invalid()),
+            Some(id) => SpreadsheetId::new(id)?,
+        };
+
+        // the tab shows up as `#gid=123`, or sometimes `?gid=123`
+        let fragment_pairs = url
+            .fragment()
+            .map(|fragment| url::form_urlencoded::parse(fragment.as_bytes()))
+            .into_iter()
+            .flatten();
+        let gid = url
+            .query_pairs()
+            .chain(fragment_pairs)
+            .find(|(key, _)| key == "gid")
+            .map(|(_, value)| value.parse::<i64>().map_err(|_| invalid()))
+            .transpose()?;
+
+        Ok(Self { id, gid })
+    }
+}
+
+impl fmt::Display for SpreadsheetLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.gid {
+            Some(gid) => f.write_str(&self.id.link_to(gid, None)),
+            None => f.write_str(&self.id.link()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpreadsheetId, SpreadsheetLink};
+
+    const ID: &str = "1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms";
+
+    fn link(s: &str) -> SpreadsheetLink {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_raw_id() {
+        let id: SpreadsheetId = ID.parse().unwrap();
+        assert_eq!(id.as_str(), ID);
+        assert!("".parse::<SpreadsheetId>().is_err());
+        assert!("not an id!".parse::<SpreadsheetId>().is_err());
+    }
+
+    #[test]
+    fn test_parse_edit_link_with_gid() {
+        let parsed = link(&format!(
+            "https://docs.google.com/spreadsheets/d/{}/edit#gid=123",
+            ID
+        ));
+        assert_eq!(parsed.id.as_str(), ID);
+        assert_eq!(parsed.gid, Some(123));
+    }
+
+    #[test]
+    fn test_parse_link_variants() {
+        let account = link(&format!(
+            "https://docs.google.com/spreadsheets/u/0/d/{}/edit?usp=sharing",
+            ID
+        ));
+        assert_eq!(account.id.as_str(), ID);
+        assert_eq!(account.gid, None);
+
+        let workspace = link(&format!(
+            "https://docs.google.com/a/example.com/spreadsheets/d/{}/edit#gid=7&range=A1:B2",
+            ID
+        ));
+        assert_eq!(workspace.id.as_str(), ID);
+        assert_eq!(workspace.gid, Some(7));
+
+        let no_scheme = link(&format!("docs.google.com/spreadsheets/d/{}", ID));
+        assert_eq!(no_scheme.id.as_str(), ID);
+
+        let query_gid = link(&format!(
+            "https://docs.google.com/spreadsheets/d/{}/edit?gid=42",
+            ID
+        ));
+        assert_eq!(query_gid.gid, Some(42));
+    }
+
+    #[test]
+    fn test_parse_rejects_other_links() {
+        assert!("https://docs.google.com/document/d/abc/edit"
+            .parse::<SpreadsheetLink>()
+            .is_err());
+        assert!("https://docs.google.com/spreadsheets/d/"
+            .parse::<SpreadsheetLink>()
+            .is_err());
+        assert!("https://docs.google.com/spreadsheets/d/abc/edit#gid=first"
+            .parse::<SpreadsheetLink>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_hosts_and_published_links() {
+        assert!(format!("https://example.com/spreadsheets/d/{}/edit", ID)
+            .parse::<SpreadsheetLink>()
+            .is_err());
+        assert!(format!("docs.google.com.example.com/spreadsheets/d/{}", ID)
+            .parse::<SpreadsheetLink>()
+            .is_err());
+        assert!(
+            "https://docs.google.com/spreadsheets/d/e/2PACX-1vQabc/pubhtml"
+                .parse::<SpreadsheetLink>()
+                .is_err()
+        );
+
+        let upper = link(&format!("https://DOCS.GOOGLE.COM/spreadsheets/d/{}/", ID));
+        assert_eq!(upper.id.as_str(), ID);
+    }
+
+    #[test]
+    fn test_links() {
+        let id = SpreadsheetId::new("abc123").unwrap();
+        assert_eq!(id.link(), "https://docs.google.com/spreadsheets/d/abc123/");
+        assert_eq!(
+            id.link_to(123, None),
+            "https://docs.google.com/spreadsheets/d/abc123/edit#gid=123"
+        );
+        assert_eq!(
+            id.link_to(0, Some("A1:C10")),
+            "https://docs.google.com/spreadsheets/d/abc123/edit#gid=0&range=A1:C10"
+        );
+        assert_eq!(
+            id.link_to_range("A1:C10"),
+            "https://docs.google.com/spreadsheets/d/abc123/edit#range=A1:C10"
+        );
+        assert_eq!(
+            link("https://docs.google.com/spreadsheets/d/abc123/edit#gid=5").to_string(),
+            "https://docs.google.com/spreadsheets/d/abc123/edit#gid=5"
+        );
+    }
+}
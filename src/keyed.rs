@@ -0,0 +1,726 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::cell::into_rows;
+use crate::util::{get_a1_notation, parse_column_notation, sheet_range};
+use crate::{
+    ApiError, BatchUpdateValuesRequest, CellValue, Dimension, GetValuesOptions, Result, Sheets,
+    ValueInputOption, ValueRange, ValueRenderOption, WriteOptions,
+};
+
+/// What [`Sheets::upsert_rows`] did.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct UpsertSummary {
+    /// The number of rows appended because their key wasn't in the sheet.
+    pub inserted: usize,
+    /// The number of rows overwritten in place.
+    pub updated: usize,
+}
+
+/// The text a key is compared by, so `42` matches `"42"`. Empty cells have no key.
+fn key_of(cell: Option<&CellValue>) -> Option<String> {
+    cell.filter(|cell| !cell.is_empty())
+        .map(|cell| cell.to_string())
+}
+
+/// The key a new row's cell is compared by: that of the value the sheet will store once it's
+/// written with `input`, so `"007"` matches the `7` an earlier write left behind.
+fn new_key_of(cell: Option<&CellValue>, input: ValueInputOption) -> Option<String> {
+    key_of(cell.map(|cell| read_back(cell, input)).as_ref())
+}
+
+/// Where each key sits in the key column below `header_rows`, by zero-indexed row. The first of
+/// any duplicates wins.
+fn index_keys(column: &[CellValue], header_rows: usize) -> HashMap<String, usize> {
+    let mut keys = HashMap::new();
+    for (index, cell) in column.iter().enumerate().skip(header_rows) {
+        if let Some(key) = key_of(Some(cell)) {
+            keys.entry(key).or_insert(index);
+        }
+    }
+    keys
+}
+
+/// The rows of an upsert, sorted by what happens to them.
+#[derive(Debug, PartialEq)]
+struct UpsertPlan {
+    /// Rows that overwrite an existing row, with its zero-indexed row number.
+    updates: Vec<(usize, Vec<CellValue>)>,
+    appends: Vec<Vec<CellValue>>,
+}
+
+/// Splits `rows` into rows that overwrite an existing row, by zero-indexed row, and rows to
+/// append.
+///
+/// When several rows share a key, the last one is written, in the place of the first. Rows
+/// without a key are always appended. Keys are compared as they'll be stored when written with
+/// `input`.
+fn plan_upsert(
+    existing: &HashMap<String, usize>,
+    key_column: usize,
+    rows: Vec<Vec<CellValue>>,
+    input: ValueInputOption,
+) -> UpsertPlan {
+    let mut updates: Vec<(usize, Vec<CellValue>)> = Vec::new();
+    let mut appends: Vec<Vec<CellValue>> = Vec::new();
+    let mut updated_at: HashMap<String, usize> = HashMap::new();
+    let mut appended_at: HashMap<String, usize> = HashMap::new();
+
+    for row in rows {
+        let key = match new_key_of(row.get(key_column), input) {
+            Some(key) => key,
+            None => {
+                appends.push(row);
+                continue;
+            }
+        };
+
+        if let Some(&index) = existing.get(&key) {
+            match updated_at.get(&key) {
+                Some(&at) => updates[at].1 = row,
+                None => {
+                    updated_at.insert(key, updates.len());
+                    updates.push((index, row));
+                }
+            }
+        } else {
+            match appended_at.get(&key) {
+                Some(&at) => appends[at] = row,
+                None => {
+                    appended_at.insert(key, appends.len());
+                    appends.push(row);
+                }
+            }
+        }
+    }
+
+    UpsertPlan { updates, appends }
+}
+
+/// How [`Sheets::upsert_rows_with`] matches and writes rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpsertOptions {
+    /// How many rows at the top of the sheet are headers, never matched or overwritten.
+    /// Defaults to 1.
+    pub header_rows: usize,
+    /// How updated and new rows are written.
+    pub write: WriteOptions,
+}
+
+impl Default for UpsertOptions {
+    fn default() -> Self {
+        Self {
+            header_rows: 1,
+            write: WriteOptions::default(),
+        }
+    }
+}
+
+/// How [`Sheets::mirror_rows_with`] mirrors a dataset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MirrorOptions {
+    /// How many rows at the top of the sheet are headers, left alone. Defaults to 1.
+    pub header_rows: usize,
+    /// Whether to only plan the changes, without making them.
+    pub dry_run: bool,
+    /// How changed cells and new rows are written.
+    pub write: WriteOptions,
+}
+
+impl Default for MirrorOptions {
+    fn default() -> Self {
+        Self {
+            header_rows: 1,
+            dry_run: false,
+            write: WriteOptions::default(),
+        }
+    }
+}
+
+/// A cell that changes to a new value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellUpdate {
+    /// The zero-indexed row, before any rows are deleted.
+    pub row: usize,
+    /// The zero-indexed column.
+    pub column: usize,
+    pub value: CellValue,
+}
+
+/// The changes that make a sheet match a dataset.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct MirrorPlan {
+    /// Cells of existing rows whose values change.
+    pub updates: Vec<CellUpdate>,
+    /// New rows, appended after the last row in the order they were given.
+    pub inserts: Vec<Vec<CellValue>>,
+    /// Zero-indexed rows that are deleted, in ascending order.
+    pub deletes: Vec<usize>,
+}
+
+impl MirrorPlan {
+    /// Whether the sheet already matches.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && self.inserts.is_empty() && self.deletes.is_empty()
+    }
+}
+
+/// The value a cell holds after `new` is written with `input`, as read with
+/// [`ValueRenderOption::Formula`].
+///
+/// With [`ValueInputOption::UserEntered`] the sheet parses text the way it parses typing, so
+/// `"12.50"`, `"1,200"`, `"$3"` and `"5%"` become numbers, `"TRUE"` a boolean, and
+/// `"2024-01-01"` or `"2024-01-01 18:00"` a date's serial number. This covers those common
+/// forms as the default `en_US` locale reads them; anything else is compared as text.
+fn read_back(new: &CellValue, input: ValueInputOption) -> CellValue {
+    let text = match (new, input) {
+        (CellValue::String(text), ValueInputOption::UserEntered) => text.trim(),
+        _ => return new.clone(),
+    };
+
+    let number = |text: &str| -> Option<f64> {
+        let text = text.strip_prefix('$').unwrap_or(text);
+        // thousands separators only count between digits, as in `1,200`
+        let digits = text.replace(',', "");
+        if text.contains(",,") || text.starts_with(',') || text.contains(",.") {
+            return None;
+        }
+        digits.parse::<f64>().ok().filter(|n| n.is_finite())
+    };
+
+    if let Some(n) = number(text) {
+        CellValue::Number(n)
+    } else if let Some(n) = text.strip_suffix('%').and_then(number) {
+        CellValue::Number(n / 100.0)
+    } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+        CellValue::Bool(text.eq_ignore_ascii_case("true"))
+    } else if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        CellValue::from(date)
+    } else if let Some(datetime) = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    {
+        CellValue::from(datetime)
+    } else {
+        new.clone()
+    }
+}
+
+/// Whether a cell already holds the value to write. Values that read back the same as they're
+/// written, such as `"42"` and `42`, match.
+fn cells_match(current: &CellValue, new: &CellValue) -> bool {
+    match (current, new) {
+        // parsed numbers can be a rounding step away from what the sheet stored
+        (CellValue::Number(a), CellValue::Number(b)) => (a - b).abs() <= 1e-9 * a.abs().max(1.0),
+        _ => current == new || (!current.is_empty() && current.to_string() == new.to_string()),
+    }
+}
+
+/// Diffs the rows below `header_rows` against `rows`, matching rows by their key. Keys and cells
+/// are compared as they'll be stored when written with `input`.
+fn plan_mirror(
+    current: &[Vec<CellValue>],
+    header_rows: usize,
+    key_column: usize,
+    rows: Vec<Vec<CellValue>>,
+    input: ValueInputOption,
+) -> MirrorPlan {
+    // the last row given for a key wins, as it would if they were written one by one
+    let mut order = Vec::new();
+    let mut wanted: HashMap<String, Vec<CellValue>> = HashMap::new();
+    for row in rows {
+        if let Some(key) = new_key_of(row.get(key_column), input) {
+            if wanted.insert(key.clone(), row).is_none() {
+                order.push(key);
+            }
+        }
+    }
+
+    let mut plan = MirrorPlan::default();
+    let mut seen = HashSet::new();
+    let empty = CellValue::Empty;
+    for (index, row) in current.iter().enumerate().skip(header_rows) {
+        let new = key_of(row.get(key_column))
+            .filter(|key| seen.insert(key.clone()))
+            .and_then(|key| wanted.get(&key));
+        let new = match new {
+            Some(new) => new,
+            // blank, keyless, duplicated or no longer in the dataset
+            None => {
+                plan.deletes.push(index);
+                continue;
+            }
+        };
+
+        for column in 0..row.len().max(new.len()) {
+            let (current, new) = (
+                row.get(column).unwrap_or(&empty),
+                new.get(column).unwrap_or(&empty),
+            );
+            if !cells_match(current, &read_back(new, input)) {
+                plan.updates.push(CellUpdate {
+                    row: index,
+                    column,
+                    value: new.clone(),
+                });
+            }
+        }
+    }
+
+    plan.inserts = order
+        .into_iter()
+        .filter(|key| !seen.contains(key))
+        .filter_map(|key| wanted.remove(&key))
+        .collect();
+    plan
+}
+
+/// Groups cell updates into one range per run of neighbouring cells in a row.
+fn update_ranges(sheet_title: &str, updates: &[CellUpdate]) -> Vec<ValueRange> {
+    let mut runs: Vec<(usize, usize, Vec<CellValue>)> = Vec::new();
+    for update in updates {
+        match runs.last_mut() {
+            Some((row, start, values))
+                if *row == update.row && *start + values.len() == update.column =>
+            {
+                values.push(update.value.clone())
+            }
+            _ => runs.push((update.row, update.column, vec![update.value.clone()])),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(row, start, values)| {
+            let a1 = get_a1_notation(
+                Some(start),
+                Some(row),
+                Some(start + values.len() - 1),
+                Some(row),
+            );
+            ValueRange::new(&sheet_range(sheet_title, Some(&a1)), vec![values])
+        })
+        .collect()
+}
+
+impl Sheets {
+    /// Reads one column of a sheet (tab), unformatted, as one cell per row.
+    pub(crate) async fn read_column(
+        &self,
+        sheet_title: &str,
+        column: usize,
+    ) -> Result<Vec<CellValue>> {
+        let range = get_a1_notation(Some(column), None, Some(column), None);
+        let options = GetValuesOptions {
+            major_dimension: Some(Dimension::COLUMNS),
+            value_render_option: Some(ValueRenderOption::UnformattedValue),
+            ..Default::default()
+        };
+        let values = self
+            .get_values_with(&sheet_range(sheet_title, Some(&range)), &options)
+            .await?;
+
+        Ok(values
+            .values
+            .and_then(|columns| columns.into_iter().next())
+            .unwrap_or_default())
+    }
+
+    /// Updates the rows whose key matches, and appends the rest.
+    ///
+    /// See [`upsert_rows_with`](Sheets::upsert_rows_with).
+    pub async fn upsert_rows<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        key_column: &str,
+        rows: Vec<Vec<T>>,
+    ) -> Result<UpsertSummary> {
+        self.upsert_rows_with(sheet_title, key_column, rows, &UpsertOptions::default())
+            .await
+    }
+
+    /// Overwrites each row of the sheet whose value in `key_column`, such as `A`, matches the
+    /// new row's value in that column, and appends the rows whose key isn't in the sheet.
+    ///
+    /// The key column is read once. Keys are compared by the value the sheet stores, so `42`
+    /// matches `"42"`, and with the default [`ValueInputOption::UserEntered`] `"007"` matches the
+    /// `7` it was stored as. The
+    /// first `options.header_rows` rows are headers: a row whose key matches a header's text is
+    /// appended rather than written over it. Every update goes out in a single
+    /// `values:batchUpdate` and every new row in a single append. Rows are written from column
+    /// `A`, and rows without a key are always appended.
+    ///
+    /// ```no_run
+    /// use googlesheets::Sheets;
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let rows = vec![vec!["sku-1", "Widget", "12"], vec!["sku-9", "Gadget", "3"]];
+    /// let summary = sheets.upsert_rows("Inventory", "A", rows).await?;
+    /// println!("{} inserted, {} updated", summary.inserted, summary.updated);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upsert_rows_with<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        key_column: &str,
+        rows: Vec<Vec<T>>,
+        options: &UpsertOptions,
+    ) -> Result<UpsertSummary> {
+        let key_index =
+            parse_column_notation(key_column).ok_or_else(|| ApiError::InvalidRange {
+                range: String::from(key_column),
+            })?;
+
+        let column = self.read_column(sheet_title, key_index).await?;
+        let existing = index_keys(&column, options.header_rows);
+        let UpsertPlan { updates, appends } = plan_upsert(
+            &existing,
+            key_index,
+            into_rows(rows),
+            options.write.value_input_option,
+        );
+        let summary = UpsertSummary {
+            inserted: appends.len(),
+            updated: updates.len(),
+        };
+
+        if !updates.is_empty() {
+            let data = updates
+                .into_iter()
+                .map(|(index, row)| {
+                    ValueRange::new(
+                        &sheet_range(sheet_title, Some(&format!("A{}", index + 1))),
+                        vec![row],
+                    )
+                })
+                .collect();
+            self.batch_update(&BatchUpdateValuesRequest::with_options(
+                data,
+                &options.write,
+            ))
+            .await?;
+        }
+        if !appends.is_empty() {
+            self.append_rows_with(sheet_title, appends, &options.write)
+                .await?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Makes the rows of a sheet match `rows`, matching rows by their value in `key_column`.
+    ///
+    /// See [`mirror_rows_with`](Sheets::mirror_rows_with).
+    pub async fn mirror_rows<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        key_column: &str,
+        rows: Vec<Vec<T>>,
+    ) -> Result<MirrorPlan> {
+        self.mirror_rows_with(sheet_title, key_column, rows, &MirrorOptions::default())
+            .await
+    }
+
+    /// Makes the rows of a sheet (tab) below its headers match `rows`, changing as little as
+    /// possible, and returns the changes.
+    ///
+    /// Rows are matched by their value in `key_column`, such as `A`, compared as with
+    /// [`upsert_rows`](Sheets::upsert_rows). Only cells whose values differ are written, so
+    /// formatting and notes on unchanged cells survive. Rows whose key isn't in `rows` are
+    /// deleted, along with blank rows and repeated keys, and new keys are appended in the order
+    /// given. Rows of `rows` without a key are ignored. Existing rows keep their order.
+    ///
+    /// Formulas are compared as formulas, and other cells as the sheet will store them: with the
+    /// default [`ValueInputOption::UserEntered`], text such as `"12.50"`, `"5%"` or
+    /// `"2024-01-01"` matches the number or date it's parsed into, so it isn't rewritten on
+    /// every run. Changed cells are written in one
+    /// `values:batchUpdate`, rows are deleted from the bottom up in one `spreadsheets.batchUpdate`,
+    /// and new rows are added in one append. With `options.dry_run`, nothing is changed.
+    ///
+    /// ```no_run
+    /// use googlesheets::{MirrorOptions, Sheets};
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let rows = vec![vec!["sku-1", "Widget", "12"], vec!["sku-2", "Gadget", "3"]];
+    /// let options = MirrorOptions {
+    ///     dry_run: true,
+    ///     ..Default::default()
+    /// };
+    /// let plan = sheets.mirror_rows_with("Inventory", "A", rows, &options).await?;
+    /// println!(
+    ///     "{} cells to change, {} rows to insert, {} to delete",
+    ///     plan.updates.len(),
+    ///     plan.inserts.len(),
+    ///     plan.deletes.len()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mirror_rows_with<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        key_column: &str,
+        rows: Vec<Vec<T>>,
+        options: &MirrorOptions,
+    ) -> Result<MirrorPlan> {
+        let key_index =
+            parse_column_notation(key_column).ok_or_else(|| ApiError::InvalidRange {
+                range: String::from(key_column),
+            })?;
+
+        let get = GetValuesOptions {
+            major_dimension: Some(Dimension::ROWS),
+            value_render_option: Some(ValueRenderOption::Formula),
+            ..Default::default()
+        };
+        let current = self
+            .get_values_with(&sheet_range(sheet_title, None), &get)
+            .await?
+            .values
+            .unwrap_or_default();
+
+        let plan = plan_mirror(
+            &current,
+            options.header_rows,
+            key_index,
+            into_rows(rows),
+            options.write.value_input_option,
+        );
+        if options.dry_run || plan.is_empty() {
+            return Ok(plan);
+        }
+
+        // cells first, while the rows they're in are still where the plan says
+        if !plan.updates.is_empty() {
+            let data = update_ranges(sheet_title, &plan.updates);
+            self.batch_update(&BatchUpdateValuesRequest::with_options(
+                data,
+                &options.write,
+            ))
+            .await?;
+        }
+        self.delete_rows(sheet_title, &plan.deletes).await?;
+        if !plan.inserts.is_empty() {
+            self.append_rows_with(sheet_title, plan.inserts.clone(), &options.write)
+                .await?;
+        }
+
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{index_keys, plan_mirror, plan_upsert, update_ranges, CellUpdate};
+    use crate::cell::into_rows;
+    use crate::{CellValue, ValueInputOption};
+
+    #[test]
+    fn test_index_keys() {
+        let column = [
+            CellValue::from("SKU"),
+            CellValue::Number(42.0),
+            CellValue::Empty,
+            CellValue::from("sku-1"),
+            CellValue::from("42"),
+        ];
+        let keys = index_keys(&column, 1);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["42"], 1);
+        assert_eq!(keys["sku-1"], 3);
+        assert!(!keys.contains_key("SKU"));
+        assert_eq!(index_keys(&column, 0)["SKU"], 0);
+    }
+
+    #[test]
+    fn test_plan_upsert() {
+        let existing = index_keys(&into_rows(vec![vec!["id", "a", "b"]]).remove(0), 1);
+        let plan = plan_upsert(
+            &existing,
+            1,
+            into_rows(vec![
+                vec!["x", "b", "first"],
+                vec!["y", "c", "new"],
+                vec!["z", "", "no key"],
+                vec!["x", "b", "second"],
+                vec!["y", "c", "newer"],
+                // matches the header's text, but the header is never overwritten
+                vec!["w", "id", "header"],
+            ]),
+            ValueInputOption::UserEntered,
+        );
+
+        assert_eq!(
+            plan.updates,
+            vec![(2, into_rows(vec![vec!["x", "b", "second"]]).remove(0))]
+        );
+        assert_eq!(
+            plan.appends,
+            into_rows(vec![
+                vec!["y", "c", "newer"],
+                vec!["z", "", "no key"],
+                vec!["w", "id", "header"]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plan_upsert_matches_stored_keys() {
+        // keys a previous upsert wrote as "007", "12.50" and "2024-01-01", read back unformatted
+        let column: Vec<CellValue> =
+            serde_json::from_value(serde_json::json!(["Key", 7, 12.5, 45292])).unwrap();
+        let existing = index_keys(&column, 1);
+        let rows = || {
+            into_rows(vec![
+                vec!["007", "again"],
+                vec!["12.50", "again"],
+                vec!["2024-01-01", "again"],
+            ])
+        };
+
+        let plan = plan_upsert(&existing, 0, rows(), ValueInputOption::UserEntered);
+        let rows_updated: Vec<usize> = plan.updates.iter().map(|(index, _)| *index).collect();
+        assert_eq!(rows_updated, vec![1, 2, 3]);
+        assert!(plan.appends.is_empty());
+
+        // written raw they'd be stored as text, which the numbers in the sheet don't match
+        let raw = plan_upsert(&existing, 0, rows(), ValueInputOption::Raw);
+        assert!(raw.updates.is_empty());
+        assert_eq!(raw.appends.len(), 3);
+    }
+
+    #[test]
+    fn test_plan_mirror() {
+        let current: Vec<Vec<CellValue>> = serde_json::from_value(serde_json::json!([
+            ["SKU", "Name", "Count"],
+            ["sku-1", "Widget", 12],
+            ["sku-2", "Gadget", 3, "stale note"],
+            [],
+            ["sku-3", "Gizmo", 1],
+            ["sku-1", "Widget", 12],
+        ]))
+        .unwrap();
+
+        let plan = plan_mirror(
+            &current,
+            1,
+            0,
+            into_rows(vec![
+                vec!["sku-4", "Doohickey", "5"],
+                vec!["sku-1", "Widget", "12"],
+                vec!["sku-2", "Gadget", "4"],
+                vec!["", "no key", "0"],
+            ]),
+            ValueInputOption::UserEntered,
+        );
+
+        assert_eq!(
+            plan.updates,
+            vec![
+                CellUpdate {
+                    row: 2,
+                    column: 2,
+                    value: CellValue::from("4"),
+                },
+                CellUpdate {
+                    row: 2,
+                    column: 3,
+                    value: CellValue::Empty,
+                },
+            ]
+        );
+        assert_eq!(
+            plan.inserts,
+            into_rows(vec![vec!["sku-4", "Doohickey", "5"]])
+        );
+        assert_eq!(plan.deletes, vec![3, 4, 5]);
+
+        let unchanged = plan_mirror(
+            &current[..2],
+            1,
+            0,
+            into_rows(vec![vec!["sku-1", "Widget", "12"]]),
+            ValueInputOption::UserEntered,
+        );
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn test_plan_mirror_compares_parsed_values() {
+        // dates, decimals and percentages as read back with the formula render option
+        let current: Vec<Vec<CellValue>> = serde_json::from_value(serde_json::json!([
+            ["SKU", "Since", "Price", "Discount", "Active"],
+            ["sku-1", 45292, 12.5, 0.05, true],
+            ["sku-2", 45292.75, 1200, 0.1, false],
+        ]))
+        .unwrap();
+        let rows = || {
+            into_rows(vec![
+                vec!["sku-1", "2024-01-01", "12.50", "5%", "TRUE"],
+                vec!["sku-2", "2024-01-01 18:00", "1,200", "10%", "false"],
+            ])
+        };
+
+        let plan = plan_mirror(&current, 1, 0, rows(), ValueInputOption::UserEntered);
+        assert!(plan.is_empty(), "{:?}", plan);
+
+        // stored as text, the dates and numbers no longer match what the sheet holds
+        let raw = plan_mirror(&current, 1, 0, rows(), ValueInputOption::Raw);
+        assert_eq!(raw.updates.len(), 7);
+
+        let changed = plan_mirror(
+            &current,
+            1,
+            0,
+            into_rows(vec![vec!["sku-1", "2024-01-02", "12.51", "5%", "TRUE"]]),
+            ValueInputOption::UserEntered,
+        );
+        let columns: Vec<usize> = changed.updates.iter().map(|u| u.column).collect();
+        assert_eq!(columns, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_mirroring_twice_changes_nothing() {
+        // what mirroring these rows left behind: the keys were parsed into numbers and a date
+        let current: Vec<Vec<CellValue>> = serde_json::from_value(serde_json::json!([
+            ["ID", "Name"],
+            [7, "Bond"],
+            [45292, "New year"],
+            [12.5, "Half"],
+        ]))
+        .unwrap();
+        let rows = into_rows(vec![
+            vec!["007", "Bond"],
+            vec!["2024-01-01", "New year"],
+            vec!["12.50", "Half"],
+        ]);
+
+        let plan = plan_mirror(&current, 1, 0, rows, ValueInputOption::UserEntered);
+        assert!(plan.is_empty(), "{:?}", plan);
+    }
+
+    #[test]
+    fn test_update_ranges() {
+        let update = |row, column| CellUpdate {
+            row,
+            column,
+            value: CellValue::from("x"),
+        };
+        let ranges: Vec<_> = update_ranges(
+            "Data",
+            &[update(2, 2), update(2, 3), update(2, 5), update(4, 0)],
+        )
+        .into_iter()
+        .map(|range| (range.range.unwrap(), range.values.unwrap()[0].len()))
+        .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (String::from("'Data'!C3:D3"), 2),
+                (String::from("'Data'!F3:F3"), 1),
+                (String::from("'Data'!A5:A5"), 1),
+            ]
+        );
+    }
+}
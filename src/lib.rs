@@ -2,121 +2,157 @@ extern crate yup_oauth2 as oauth;
 
 use std::fmt;
 
-use oauth::{AccessToken, InstalledFlowAuthenticator, InstalledFlowReturnMethod};
-use reqwest::{header, Client, Method, Request, StatusCode, Url};
+use oauth::AccessToken;
+use reqwest::{Method, StatusCode};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 
 /// Base endpoint for the Google Sheets API.
 const BASE_ENDPOINT: &str = "https://sheets.googleapis.com/v4/";
 
+mod cell;
+mod chunk;
+mod client;
+mod data_filter;
+mod dimension;
+mod id;
+mod keyed;
+pub mod metrics;
+mod records;
+mod row;
+pub mod serial;
+mod stream;
 pub mod util;
 
-use util::get_a1_notation;
+use cell::into_rows;
+pub use cell::CellValue;
+use client::SPREADSHEETS_SCOPE;
+pub use client::{SheetsClient, SheetsClientBuilder};
+pub use data_filter::{
+    BatchClearValuesByDataFilterResponse, BatchGetValuesByDataFilterResponse,
+    BatchUpdateValuesByDataFilterResponse, DataFilter, DataFilterValueRange,
+    DeveloperMetadataLocation, DeveloperMetadataLocationMatchingStrategy,
+    DeveloperMetadataLocationType, DeveloperMetadataLookup, DeveloperMetadataVisibility,
+    DimensionRange, GridRange, MatchedValueRange, UpdateValuesByDataFilterResponse,
+};
+use dimension::GridSize;
+pub use dimension::{GridProperties, SheetProperties};
+pub use id::{SpreadsheetId, SpreadsheetLink};
+pub use keyed::{CellUpdate, MirrorOptions, MirrorPlan, UpsertOptions, UpsertSummary};
+pub use records::{ReadRecordsOptions, WriteRecordsOptions};
+pub use row::{FromCell, SheetColumn, SheetRow, ToCell};
+pub use stream::{StreamReadOptions, StreamWriteOptions, StreamWriteSummary};
+use util::{get_a1_notation, sheet_or_range, sheet_range};
 
+#[cfg(feature = "derive")]
+pub use googlesheets_derive::SheetRow;
+
+/// Not public API; used by code generated with `#[derive(SheetRow)]`.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::row::{build_row, read_cell};
+}
+
+/// A handle to a single spreadsheet.
+///
+/// Handles are cheap to create and clone. Open several from one [`SheetsClient`] to work with
+/// many spreadsheets through the same authentication and HTTP client.
+#[derive(Clone)]
 pub struct Sheets {
-    token: AccessToken,
-    client: Client,
-    sheet_id: String,
+    client: SheetsClient,
+    sheet_id: SpreadsheetId,
+    gid: Option<i64>,
 }
 
 type Result<T, E = ApiError> = std::result::Result<T, E>;
 
 impl Sheets {
+    /// `sheet_id` may be a raw spreadsheet id or a link to the spreadsheet.
     pub fn new(token: AccessToken, sheet_id: &str) -> Result<Self> {
-        let client = Client::builder().build().context(ClientBuildFail {})?;
-
-        Ok(Self {
-            token,
-            client,
-            sheet_id: String::from(sheet_id),
-        })
+        SheetsClient::new(token)?.open(sheet_id)
     }
 
+    /// `sheet_id` may be a raw spreadsheet id or a link to the spreadsheet.
     pub async fn initialize(sheet_id: &str) -> Result<Self> {
-        let token = Sheets::authenticate().await?;
-        Sheets::new(token, sheet_id)
+        SheetsClient::initialize().await?.open(sheet_id)
     }
 
-    pub fn get_link_to_sheet(&self) -> String {
-        format!("https://docs.google.com/spreadsheets/d/{}/", self.sheet_id)
+    /// The client this handle sends its requests through.
+    pub fn client(&self) -> &SheetsClient {
+        &self.client
     }
 
-    pub async fn authenticate() -> Result<AccessToken> {
-        // Read application secret from a file. Sometimes it's easier to compile it directly into the binary.
-        let secret = oauth::read_application_secret("client_secret.json")
-            .await
-            .context(AuthenticateError {
-                meta: "Failed to configure secret from 'client_secret.json'",
-            })?;
+    /// A snapshot of the request counters shared by every handle on this client.
+    ///
+    /// Returns `None` unless the client was built with
+    /// [`SheetsClientBuilder::collect_stats`].
+    pub fn stats(&self) -> Option<metrics::Stats> {
+        self.client.stats()
+    }
 
-        // All authentication tokens are persisted to a file named `tokencache.json`.
-        // The authenticator takes care of caching tokens to disk and refreshing tokens once they've expired.
-        let auth =
-            InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::HTTPRedirect)
-                .persist_tokens_to_disk("tokencache.json")
-                .build()
-                .await
-                .context(AuthenticateError { meta: "Failed to build auth from secret. Try deleting 'tokencache.json' and running again."})?;
+    pub fn spreadsheet_id(&self) -> &SpreadsheetId {
+        &self.sheet_id
+    }
 
-        let scope = &["https://www.googleapis.com/auth/spreadsheets"];
+    /// The id of the tab this handle was opened on, if it was opened from a link to one.
+    pub fn gid(&self) -> Option<i64> {
+        self.gid
+    }
 
-        let token = auth.token(scope).await.context(TokenError {
-            scope: String::from(scope[0]),
-        })?;
+    /// Point this handle at the tab with the given id.
+    pub fn with_gid(mut self, gid: i64) -> Self {
+        self.gid = Some(gid);
+        self
+    }
 
-        Ok(token)
+    /// Link to the spreadsheet, on the tab this handle tracks if there is one.
+    pub fn get_link_to_sheet(&self) -> String {
+        match self.gid {
+            Some(gid) => self.sheet_id.link_to(gid, None),
+            None => self.sheet_id.link(),
+        }
     }
 
-    /// Makes a request to the Google Sheets API
-    ///
-    /// # Arguments
+    /// Link to a range, such as `A1:C10`, on the tab this handle tracks.
     ///
-    /// - `method`: The type of request to make (GET, POST, etc.)
-    /// - `path`: The path to the endpoint (for example: `spreadsheets/{spreadsheetId}/values/{range}:append`)
-    /// - `body`: The body of the request
-    /// - `query_params`: The query parameters to add on to the request, in a list of tuples with `Vec<(parameter_name, parameter_value)>`
-    async fn request<T: Serialize>(
-        &self,
-        method: Method,
-        path: &str,
-        body: T,
-        query_params: Option<Vec<(&str, &str)>>,
-    ) -> Request {
-        // confirm URL can parse before continuing
-        let url = Url::parse(BASE_ENDPOINT).unwrap().join(&path).unwrap();
-
-        // TODO-- use `self.token = Sheets::authenticate().await.unwrap()` to attempt to read token from cache
-        // Note: this would require a mutable reference to `&mut self` in practically every method for `google_sheets::Sheets`
-        if self.token.is_expired() {
-            panic!("Token is expired");
+    /// Without a tracked tab the link names no tab, and opens on whichever tab the spreadsheet
+    /// opens on; use [`with_gid`](Sheets::with_gid) to point it at a specific one.
+    pub fn get_link_to_range(&self, range: &str) -> String {
+        match self.gid {
+            Some(gid) => self.sheet_id.link_to(gid, Some(range)),
+            None => self.sheet_id.link_to_range(range),
         }
+    }
 
-        let bearer_token =
-            header::HeaderValue::from_str(&format!("Bearer {}", &self.token.as_str())).unwrap();
-
-        // Set the default headers.
-        let mut headers = header::HeaderMap::new();
-        headers.append(header::AUTHORIZATION, bearer_token);
-        headers.append(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("application/json"),
-        );
-
-        let mut request_builder = self
+    /// Returns the spreadsheet's title, locale and time zone.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.get`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.get`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/get
+    pub async fn properties(&self) -> Result<SpreadsheetProperties> {
+        let request = self
             .client
-            .request(Method::from(&method), url)
-            .headers(headers);
+            .request(
+                Method::GET,
+                &["spreadsheets", self.sheet_id.as_str()],
+                EmptyBody {},
+                Some(vec![("fields", "properties")]),
+            )
+            .await?;
 
-        if let Some(val) = query_params {
-            request_builder = request_builder.query(&val);
-        }
+        let spreadsheet: Spreadsheet = self
+            .client
+            .execute("spreadsheets.get", self.sheet_id.as_str(), request)
+            .await?;
+        Ok(spreadsheet.properties)
+    }
 
-        if method != Method::GET && method != Method::DELETE {
-            request_builder = request_builder.json(&body);
-        }
+    pub async fn authenticate() -> Result<AccessToken> {
+        let auth = SheetsClient::authenticator().await?;
 
-        request_builder.build().unwrap()
+        auth.token(&[SPREADSHEETS_SCOPE]).await.context(TokenError {
+            scope: String::from(SPREADSHEETS_SCOPE),
+        })
     }
 
     /// Appends values within new row under existing data.
@@ -124,124 +160,437 @@ impl Sheets {
     /// See [Google Sheets Docs: `spreadsheets.values.append`]
     ///
     /// [Google Sheets Docs: `spreadsheets.values.append`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/append
-    pub async fn append(&self, data: Vec<String>) -> Result<UpdateValuesResponse> {
+    pub async fn append<T: Into<CellValue>>(&self, data: Vec<T>) -> Result<AppendValuesResponse> {
+        self.append_with(data, &WriteOptions::default()).await
+    }
+
+    /// Appends values within new row under existing data, written according to `options`.
+    pub async fn append_with<T: Into<CellValue>>(
+        &self,
+        data: Vec<T>,
+        options: &WriteOptions,
+    ) -> Result<AppendValuesResponse> {
+        let last_column = data.len().saturating_sub(1);
+        self.append_to_range(
+            &get_a1_notation(Some(0), None, Some(last_column), None),
+            vec![data],
+            options,
+        )
+        .await
+    }
+
+    /// Appends rows after the table found in a sheet or range.
+    ///
+    /// `sheet_title_or_range` is either the title of a sheet, such as `Q1 Sales`, or a range that
+    /// names its sheet, such as `'Q1 Sales'!A1:D1`. The API looks for a table in that range and
+    /// writes the rows after its last row, starting in the table's first column. Every row is sent
+    /// in a single request.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.append`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.append`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/append
+    pub async fn append_rows<T: Into<CellValue>>(
+        &self,
+        sheet_title_or_range: &str,
+        rows: Vec<Vec<T>>,
+    ) -> Result<AppendValuesResponse> {
+        self.append_rows_with(sheet_title_or_range, rows, &WriteOptions::default())
+            .await
+    }
+
+    /// Like [`append_rows`](Sheets::append_rows), written according to `options`.
+    pub async fn append_rows_with<T: Into<CellValue>>(
+        &self,
+        sheet_title_or_range: &str,
+        rows: Vec<Vec<T>>,
+        options: &WriteOptions,
+    ) -> Result<AppendValuesResponse> {
+        self.append_to_range(&sheet_or_range(sheet_title_or_range), rows, options)
+            .await
+    }
+
+    /// Calls `spreadsheets.values.append` with `range` exactly as given.
+    async fn append_to_range<T: Into<CellValue>>(
+        &self,
+        range: &str,
+        rows: Vec<Vec<T>>,
+        options: &WriteOptions,
+    ) -> Result<AppendValuesResponse> {
         let request = self
+            .client
             .request(
                 Method::POST,
-                &format!(
-                    "spreadsheets/{}/values/{}:append",
-                    self.sheet_id,
-                    get_a1_notation(Some(0), None, Some(data.len()), None)
-                ),
+                &[
+                    "spreadsheets",
+                    self.sheet_id.as_str(),
+                    "values",
+                    &format!("{}:append", range),
+                ],
                 ValueRange {
-                    major_dimension: None,
-                    values: Some(vec![data]),
-                    range: None,
+                    major_dimension: Some(Dimension::ROWS),
+                    values: Some(into_rows(rows)),
+                    range: Some(String::from(range)),
                 },
-                Some(vec![
-                    ("valueInputOption", "USER_ENTERED"),
-                    ("insertDataOption", "INSERT_ROWS"),
-                ]),
+                Some(options.append_query()),
             )
-            .await;
-
-        let res = self.client.execute(request).await.unwrap();
+            .await?;
 
-        match res.status() {
-            StatusCode::OK => Ok(res.json().await.unwrap()),
-            status_code => Err(ApiError::GoogleSheetsApi {
-                status_code,
-                body: res.text().await.unwrap(),
-            }),
-        }
+        self.client
+            .execute(
+                "spreadsheets.values.append",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
     }
 
+    /// Writes values to one or more ranges, each `ValueRange` carrying its own range.
+    ///
+    /// Requests larger than the client's limits (see
+    /// [`SheetsClientBuilder::max_cells_per_request`]) are split into blocks of rows and sent
+    /// as several requests, a few at a time. The responses are merged into one, in order.
+    ///
     /// Call the [`spreadsheets.values.batchUpdate` endpoint]:
     ///
+    /// ```no_run
+    /// use googlesheets::{BatchUpdateValuesRequest, Sheets, ValueRange};
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let request = BatchUpdateValuesRequest::new(vec![
+    ///     ValueRange::new("Summary!A1", vec![vec!["Total", "=SUM(Data!B:B)"]]),
+    ///     ValueRange::new("'Q1 Sales'!A1:B1", vec![vec!["Region", "Amount"]]),
+    /// ]);
+    /// let response = sheets.batch_update(&request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
     /// [`spreadsheets.values.batchUpdate` endpoint]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchUpdate
-    #[allow(dead_code)]
-    pub async fn batch_update(&self, data: Vec<Vec<String>>) -> Result<BatchUpdateValuesResponse> {
+    pub async fn batch_update(
+        &self,
+        request: &BatchUpdateValuesRequest,
+    ) -> Result<BatchUpdateValuesResponse> {
+        self.chunked_batch_update(request).await
+    }
+
+    /// Sends a single `values:batchUpdate` request, however large.
+    async fn send_batch_update(
+        &self,
+        request: &BatchUpdateValuesRequest,
+    ) -> Result<BatchUpdateValuesResponse> {
         let request = self
+            .client
             .request(
                 Method::POST,
-                &format!("spreadsheets/{}/values:batchUpdate", self.sheet_id),
-                &data,
-                Some(vec![
-                    ("valueInputOption", "USER_ENTERED"),
-                    ("insertDataOption", "INSERT_ROWS"),
-                ]),
+                &["spreadsheets", self.sheet_id.as_str(), "values:batchUpdate"],
+                request,
+                None,
             )
-            .await;
-        let res = self.client.execute(request).await.unwrap();
-        match res.status() {
-            StatusCode::OK => Ok(res.json().await.unwrap()),
-            status_code => Err(ApiError::GoogleSheetsApi {
-                status_code,
-                body: res.text().await.unwrap(),
-            }),
-        }
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchUpdate",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+
+    /// Writes values to one or more ranges, written according to `options`.
+    ///
+    /// `options.insert_data_option` is ignored; it only applies to appends.
+    pub async fn batch_update_with(
+        &self,
+        data: Vec<ValueRange>,
+        options: &WriteOptions,
+    ) -> Result<BatchUpdateValuesResponse> {
+        self.batch_update(&BatchUpdateValuesRequest::with_options(data, options))
+            .await
     }
 
-    pub async fn clear_sheet(&self) -> Result<UpdateValuesResponse> {
+    /// Clears every value on a sheet (tab), keeping formatting and notes.
+    pub async fn clear_sheet(&self, sheet_title: &str) -> Result<ClearValuesResponse> {
+        self.clear_range(&sheet_range(sheet_title, None)).await
+    }
+
+    /// Clears the values in a range, keeping formatting and notes.
+    ///
+    /// `range` is in A1 notation, such as `'Q1 Sales'!A2:D`, or the quoted title of a sheet.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.clear`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.clear`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/clear
+    pub async fn clear_range(&self, range: &str) -> Result<ClearValuesResponse> {
         let request = self
+            .client
             .request(
                 Method::POST,
-                &format!("spreadsheets/{}/values/Sheet1:clear", self.sheet_id),
+                &[
+                    "spreadsheets",
+                    self.sheet_id.as_str(),
+                    "values",
+                    &format!("{}:clear", range),
+                ],
                 EmptyBody {},
                 None,
             )
-            .await;
-
-        let res = self.client.execute(request).await.unwrap();
-        match res.status() {
-            StatusCode::OK => Ok(res.json().await.unwrap()),
-            s => Err(ApiError::GoogleSheetsApi {
-                status_code: s,
-                body: res.text().await.unwrap(),
-            }),
-        }
+            .await?;
+
+        self.client
+            .execute("spreadsheets.values.clear", self.sheet_id.as_str(), request)
+            .await
     }
 
+    /// Clears the values in several ranges with one request, keeping formatting and notes.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.batchClear`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.batchClear`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchClear
+    pub async fn batch_clear(&self, ranges: &[&str]) -> Result<BatchClearValuesResponse> {
+        let request = self
+            .client
+            .request(
+                Method::POST,
+                &["spreadsheets", self.sheet_id.as_str(), "values:batchClear"],
+                BatchClearValuesRequest { ranges },
+                None,
+            )
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchClear",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+
+    /// Returns the values in a range, using the API's default render options.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.get`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.get`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/get
+    pub async fn get_values(&self, range: &str) -> Result<ValueRange> {
+        self.get_values_with(range, &GetValuesOptions::default())
+            .await
+    }
+
+    /// Returns the values in a range, rendered according to `options`.
+    pub async fn get_values_with(
+        &self,
+        range: &str,
+        options: &GetValuesOptions,
+    ) -> Result<ValueRange> {
+        let request = self
+            .client
+            .request(
+                Method::GET,
+                &["spreadsheets", self.sheet_id.as_str(), "values", range],
+                EmptyBody {},
+                Some(options.query()),
+            )
+            .await?;
+
+        self.client
+            .execute("spreadsheets.values.get", self.sheet_id.as_str(), request)
+            .await
+    }
+
+    /// Returns the values in several ranges with one request, using the API's default render options.
+    ///
+    /// See [Google Sheets Docs: `spreadsheets.values.batchGet`]
+    ///
+    /// [Google Sheets Docs: `spreadsheets.values.batchGet`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchGet
+    pub async fn batch_get(&self, ranges: &[&str]) -> Result<BatchGetValuesResponse> {
+        self.batch_get_with(ranges, &GetValuesOptions::default())
+            .await
+    }
+
+    /// Returns the values in several ranges with one request, rendered according to `options`.
+    pub async fn batch_get_with(
+        &self,
+        ranges: &[&str],
+        options: &GetValuesOptions,
+    ) -> Result<BatchGetValuesResponse> {
+        let mut query: Vec<(&str, &str)> = ranges.iter().map(|range| ("ranges", *range)).collect();
+        query.extend(options.query());
+
+        let request = self
+            .client
+            .request(
+                Method::GET,
+                &["spreadsheets", self.sheet_id.as_str(), "values:batchGet"],
+                EmptyBody {},
+                Some(query),
+            )
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.batchGet",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+
+    /// Replaces everything on a sheet (tab) with `value`, starting at `A1`.
+    ///
+    /// Same as [`replace_sheet`](Sheets::replace_sheet); the sheet is never left blank.
     #[allow(dead_code)]
-    pub async fn refresh_entire_sheet(
+    pub async fn refresh_entire_sheet<T: Into<CellValue>>(
         &self,
-        value: Vec<Vec<String>>,
+        sheet_title: &str,
+        value: Vec<Vec<T>>,
     ) -> Result<UpdateValuesResponse> {
-        self.clear_sheet().await?;
-        self.update_values("A1", value).await
+        self.replace_sheet(sheet_title, value).await
+    }
+
+    /// Like [`refresh_entire_sheet`](Sheets::refresh_entire_sheet), written according to `options`.
+    pub async fn refresh_entire_sheet_with<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        value: Vec<Vec<T>>,
+        options: &WriteOptions,
+    ) -> Result<UpdateValuesResponse> {
+        self.replace_sheet_with(sheet_title, value, options).await
+    }
+
+    /// Replaces everything on a sheet (tab) with `value`, starting at `A1`, without ever leaving
+    /// the sheet blank.
+    ///
+    /// The new values are written over the old ones first, with short rows padded so that every
+    /// cell in the new extent is overwritten. Only then are the rows below and the columns to the
+    /// right of the new data cleared, up to the edges of the sheet's grid. If the write fails, the
+    /// sheet still holds its old contents; readers never see it empty.
+    pub async fn replace_sheet<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        value: Vec<Vec<T>>,
+    ) -> Result<UpdateValuesResponse> {
+        self.replace_sheet_with(sheet_title, value, &WriteOptions::default())
+            .await
+    }
+
+    /// Like [`replace_sheet`](Sheets::replace_sheet), written according to `options`.
+    pub async fn replace_sheet_with<T: Into<CellValue>>(
+        &self,
+        sheet_title: &str,
+        value: Vec<Vec<T>>,
+        options: &WriteOptions,
+    ) -> Result<UpdateValuesResponse> {
+        let rows = pad_rows(into_rows(value));
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        let updated = if height > 0 && width > 0 {
+            self.update_values_with(&sheet_range(sheet_title, Some("A1")), rows, options)
+                .await?
+        } else {
+            UpdateValuesResponse::default()
+        };
+
+        // the Values API rejects ranges past the grid, so the leftovers stop at its edges
+        let grid = self.grid_size(sheet_title).await?;
+        let leftovers = leftover_ranges(sheet_title, height, width, grid);
+        let leftovers: Vec<&str> = leftovers.iter().map(String::as_str).collect();
+        if !leftovers.is_empty() {
+            self.batch_clear(&leftovers).await?;
+        }
+
+        Ok(updated)
     }
 
     #[allow(dead_code)]
-    pub async fn update_values(
+    pub async fn update_values<T: Into<CellValue>>(
         &self,
         range: &str,
-        value: Vec<Vec<String>>,
+        value: Vec<Vec<T>>,
     ) -> Result<UpdateValuesResponse> {
+        self.update_values_with(range, value, &WriteOptions::default())
+            .await
+    }
+
+    /// Sets values in a range, written according to `options`.
+    ///
+    /// Writes larger than the client's limits are split into blocks of rows, as with
+    /// [`batch_update`](Sheets::batch_update).
+    ///
+    /// `options.insert_data_option` is ignored; it only applies to appends.
+    pub async fn update_values_with<T: Into<CellValue>>(
+        &self,
+        range: &str,
+        value: Vec<Vec<T>>,
+        options: &WriteOptions,
+    ) -> Result<UpdateValuesResponse> {
+        let value_range = ValueRange {
+            major_dimension: Some(Dimension::ROWS),
+            range: Some(range.to_string()),
+            values: Some(into_rows(value)),
+        };
+
+        // too large for one request; send it in pieces through values:batchUpdate
+        let batch = BatchUpdateValuesRequest::with_options(vec![value_range], options);
+        if let Some(response) = self.chunked_update(&batch).await? {
+            return Ok(response);
+        }
+
         let request = self
+            .client
             .request(
                 Method::PUT,
-                &format!("spreadsheets/{}/values/{}", self.sheet_id, range),
-                ValueRange {
-                    major_dimension: Some(Dimension::ROWS),
-                    range: Some(range.to_string()),
-                    values: Some(value),
-                },
-                Some(vec![
-                    ("valueInputOption", "USER_ENTERED"),
-                    ("responseValueRenderOption", "FORMATTED_VALUE"),
-                    ("responseDateTimeRenderOption", "FORMATTED_STRING"),
-                ]),
+                &["spreadsheets", self.sheet_id.as_str(), "values", range],
+                &batch.data[0],
+                Some(options.update_query()),
             )
-            .await;
-        let res = self.client.execute(request).await.unwrap();
-        match res.status() {
-            StatusCode::OK => Ok(res.json().await.unwrap()),
-            status_code => Err(ApiError::GoogleSheetsApi {
-                status_code,
-                body: res.text().await.unwrap(),
-            }),
-        }
+            .await?;
+
+        self.client
+            .execute(
+                "spreadsheets.values.update",
+                self.sheet_id.as_str(),
+                request,
+            )
+            .await
+    }
+}
+
+/// Pads every row with empty cells to the length of the longest row.
+///
+/// Writing the padding clears whatever was in those cells before.
+fn pad_rows(mut rows: Vec<Vec<CellValue>>) -> Vec<Vec<CellValue>> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, CellValue::Empty);
+    }
+    rows
+}
+
+/// The ranges of a sheet's `grid` outside a `height` by `width` block anchored at `A1`: every
+/// row below it, and the columns to the right of it. Parts that lie outside the grid are left out.
+fn leftover_ranges(sheet_title: &str, height: usize, width: usize, grid: GridSize) -> Vec<String> {
+    if height == 0 || width == 0 {
+        return vec![sheet_range(sheet_title, None)];
+    }
+    if grid.rows == 0 || grid.columns == 0 {
+        return Vec::new();
+    }
+
+    let mut ranges: Vec<String> = grid.rows_from(sheet_title, height).into_iter().collect();
+    if width < grid.columns {
+        ranges.push(sheet_range(
+            sheet_title,
+            Some(&get_a1_notation(
+                Some(width),
+                Some(0),
+                Some(grid.columns - 1),
+                Some(height.min(grid.rows) - 1),
+            )),
+        ));
     }
+    ranges
 }
 
 #[derive(Debug, Snafu)]
@@ -255,9 +604,55 @@ pub enum ApiError {
     #[snafu(display("Client failed to build: {}", source))]
     ClientBuildFail { source: reqwest::Error },
 
+    #[snafu(display("Client needs either a token or an authenticator"))]
+    MissingAuth,
+
     #[snafu(display("Token does not have proper scope {}: {}", scope, source))]
     TokenError { source: oauth::Error, scope: String },
 
+    #[snafu(display("Not a spreadsheet id or link: {}", input))]
+    InvalidSpreadsheetId { input: String },
+
+    #[snafu(display("Not a range with a starting cell: {}", range))]
+    InvalidRange { range: String },
+
+    #[snafu(display("Token is expired"))]
+    TokenExpired,
+
+    #[snafu(display("Request to Google Sheets API failed: {}", source))]
+    RequestError { source: reqwest::Error },
+
+    #[snafu(display("Could not parse response from Google Sheets API: {}", source))]
+    ParseError { source: serde_json::Error },
+
+    #[snafu(display("Could not read row {}: {}", row, source))]
+    RecordError {
+        /// The row's number as shown in the sheet, starting at 1.
+        row: usize,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Could not write record: {}", source))]
+    SerializeRecord { source: serde_json::Error },
+
+    #[snafu(display("Field {} has no column in the header row", field))]
+    UnknownField { field: String },
+
+    #[snafu(display("Could not read column {}: {}", column, message))]
+    InvalidCell { column: String, message: String },
+
+    #[snafu(display("Unknown time zone: {}", name))]
+    InvalidTimeZone { name: String },
+
+    #[snafu(display("No sheet titled {}", title))]
+    SheetNotFound { title: String },
+
+    #[snafu(display(
+        "Can't delete every row of sheet {}; a sheet keeps at least one",
+        title
+    ))]
+    DeleteAllRows { title: String },
+
     #[snafu(display("Error from Google Sheets API. {} {}", status_code, body))]
     GoogleSheetsApi {
         status_code: StatusCode,
@@ -269,7 +664,7 @@ pub enum ApiError {
 #[derive(Serialize)]
 pub struct EmptyBody {}
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Dimension {
     /// Operates on the rows of a sheet.
     #[serde(rename = "ROWS")]
@@ -279,6 +674,214 @@ pub enum Dimension {
     COLUMNS,
 }
 
+impl Dimension {
+    /// The name the API uses for this value, as sent in query parameters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::ROWS => "ROWS",
+            Dimension::COLUMNS => "COLUMNS",
+        }
+    }
+}
+
+/// How values should be represented in the output.
+///
+/// See [Google Sheets Docs: `ValueRenderOption`]
+///
+/// [Google Sheets Docs: `ValueRenderOption`]: https://developers.google.com/sheets/api/reference/rest/v4/ValueRenderOption
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValueRenderOption {
+    /// Values will be calculated & formatted according to the cell's formatting, so `1.23` in a currency cell reads as `"$1.23"`.
+    #[serde(rename = "FORMATTED_VALUE")]
+    FormattedValue,
+    /// Values will be calculated, but not formatted, so `1.23` reads as the number `1.23`.
+    #[serde(rename = "UNFORMATTED_VALUE")]
+    UnformattedValue,
+    /// Values will not be calculated; formulas are returned as written, such as `"=A1+1"`.
+    #[serde(rename = "FORMULA")]
+    Formula,
+}
+
+impl ValueRenderOption {
+    /// The name the API uses for this value, as sent in query parameters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValueRenderOption::FormattedValue => "FORMATTED_VALUE",
+            ValueRenderOption::UnformattedValue => "UNFORMATTED_VALUE",
+            ValueRenderOption::Formula => "FORMULA",
+        }
+    }
+}
+
+/// How dates, times, and durations should be represented in the output.
+///
+/// Ignored if the value render option is [`ValueRenderOption::FormattedValue`].
+///
+/// See [Google Sheets Docs: `DateTimeRenderOption`]
+///
+/// [Google Sheets Docs: `DateTimeRenderOption`]: https://developers.google.com/sheets/api/reference/rest/v4/DateTimeRenderOption
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateTimeRenderOption {
+    /// Dates are output as doubles in "serial number" format: days since December 30th 1899, with the time as the fractional part.
+    #[serde(rename = "SERIAL_NUMBER")]
+    SerialNumber,
+    /// Dates are output as strings in the number format of the cell.
+    #[serde(rename = "FORMATTED_STRING")]
+    FormattedString,
+}
+
+impl DateTimeRenderOption {
+    /// The name the API uses for this value, as sent in query parameters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateTimeRenderOption::SerialNumber => "SERIAL_NUMBER",
+            DateTimeRenderOption::FormattedString => "FORMATTED_STRING",
+        }
+    }
+}
+
+/// How input data should be interpreted.
+///
+/// See [Google Sheets Docs: `ValueInputOption`]
+///
+/// [Google Sheets Docs: `ValueInputOption`]: https://developers.google.com/sheets/api/reference/rest/v4/ValueInputOption
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValueInputOption {
+    /// The values will be stored as-is, so `"=1+2"` is stored as that text rather than as a formula.
+    #[serde(rename = "RAW")]
+    Raw,
+    /// The values will be parsed as if typed into the UI: numbers, dates and formulas are recognized.
+    #[serde(rename = "USER_ENTERED")]
+    UserEntered,
+}
+
+impl ValueInputOption {
+    /// The name the API uses for this value, as sent in query parameters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValueInputOption::Raw => "RAW",
+            ValueInputOption::UserEntered => "USER_ENTERED",
+        }
+    }
+}
+
+/// How existing data is changed when new data is appended.
+///
+/// See [Google Sheets Docs: `InsertDataOption`]
+///
+/// [Google Sheets Docs: `InsertDataOption`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/append#InsertDataOption
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InsertDataOption {
+    /// The new data overwrites existing data in the areas it is written, after the last row of the table.
+    #[serde(rename = "OVERWRITE")]
+    Overwrite,
+    /// Rows are inserted for the new data, pushing anything below the table down.
+    #[serde(rename = "INSERT_ROWS")]
+    InsertRows,
+}
+
+impl InsertDataOption {
+    /// The name the API uses for this value, as sent in query parameters.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InsertDataOption::Overwrite => "OVERWRITE",
+            InsertDataOption::InsertRows => "INSERT_ROWS",
+        }
+    }
+}
+
+/// Options for writing values, accepted by every write method.
+///
+/// The default interprets values as if typed into the UI and inserts new rows when appending.
+/// Use [`ValueInputOption::Raw`] for user-supplied strings that must not be parsed as dates or formulas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub value_input_option: ValueInputOption,
+    /// Only used when appending.
+    pub insert_data_option: InsertDataOption,
+    /// Whether the response should include the values of the cells that were written.
+    pub include_values_in_response: Option<bool>,
+    /// How values in the response should be rendered.
+    pub response_value_render_option: Option<ValueRenderOption>,
+    /// How dates in the response should be rendered.
+    pub response_date_time_render_option: Option<DateTimeRenderOption>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            value_input_option: ValueInputOption::UserEntered,
+            insert_data_option: InsertDataOption::InsertRows,
+            include_values_in_response: None,
+            response_value_render_option: None,
+            response_date_time_render_option: None,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Store values exactly as given, without parsing.
+    pub fn raw() -> Self {
+        Self {
+            value_input_option: ValueInputOption::Raw,
+            ..Default::default()
+        }
+    }
+
+    /// Query parameters for `spreadsheets.values.update`.
+    fn update_query(&self) -> Vec<(&'static str, &'static str)> {
+        let mut params = vec![("valueInputOption", self.value_input_option.as_str())];
+        if let Some(include) = self.include_values_in_response {
+            params.push((
+                "includeValuesInResponse",
+                if include { "true" } else { "false" },
+            ));
+        }
+        if let Some(render) = self.response_value_render_option {
+            params.push(("responseValueRenderOption", render.as_str()));
+        }
+        if let Some(render) = self.response_date_time_render_option {
+            params.push(("responseDateTimeRenderOption", render.as_str()));
+        }
+        params
+    }
+
+    /// Query parameters for `spreadsheets.values.append`.
+    fn append_query(&self) -> Vec<(&'static str, &'static str)> {
+        let mut params = self.update_query();
+        params.insert(1, ("insertDataOption", self.insert_data_option.as_str()));
+        params
+    }
+}
+
+/// Options for reading values. Anything left as `None` uses the API's default.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GetValuesOptions {
+    /// Defaults to [`Dimension::ROWS`].
+    pub major_dimension: Option<Dimension>,
+    /// Defaults to [`ValueRenderOption::FormattedValue`].
+    pub value_render_option: Option<ValueRenderOption>,
+    /// Defaults to [`DateTimeRenderOption::SerialNumber`].
+    pub date_time_render_option: Option<DateTimeRenderOption>,
+}
+
+impl GetValuesOptions {
+    /// The options as query parameters.
+    fn query(&self) -> Vec<(&'static str, &'static str)> {
+        let mut params = Vec::new();
+        if let Some(dimension) = self.major_dimension {
+            params.push(("majorDimension", dimension.as_str()));
+        }
+        if let Some(render) = self.value_render_option {
+            params.push(("valueRenderOption", render.as_str()));
+        }
+        if let Some(render) = self.date_time_render_option {
+            params.push(("dateTimeRenderOption", render.as_str()));
+        }
+        params
+    }
+}
+
 /// Data within the range of the spreadsheet.
 ///
 /// See more at [Google Sheets Docs for `ValueRange`]
@@ -293,7 +896,7 @@ pub struct ValueRange {
     /// When appending values, this field represents the range to search for a table, after which values will be appended.
     pub range: Option<String>,
     /// The values
-    pub values: Option<Vec<Vec<String>>>,
+    pub values: Option<Vec<Vec<CellValue>>>,
     /// The major dimension of the values.
     ///
     /// For output, if the spreadsheet data is: A1=1,B1=2,A2=3,B2=4, then requesting range=A1:B2,majorDimension=ROWS will return \[\[1,2\],\[3,4\]\], whereas requesting range=A1:B2,majorDimension=COLUMNS will return \[\[1,3\],\[2,4\]\].
@@ -305,6 +908,178 @@ pub struct ValueRange {
     pub major_dimension: Option<Dimension>,
 }
 
+/// The response returned from appending values.
+///
+/// See [Google Sheets Docs: `AppendValuesResponse`]
+///
+/// [Google Sheets Docs: `AppendValuesResponse`]: https://developers.google.com/sheets/api/reference/rest/v4/AppendValuesResponse
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct AppendValuesResponse {
+    /// The spreadsheet the updates were applied to.
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The range (in A1 notation) of the table that values are being appended to, before the values were appended.
+    /// Empty if no table was found.
+    #[serde(rename = "tableRange")]
+    pub table_range: Option<String>,
+    /// Information about the updates that were applied.
+    pub updates: Option<UpdateValuesResponse>,
+}
+
+impl AppendValuesResponse {
+    /// The zero-indexed rows that were written, parsed from `updates.updated_range`.
+    ///
+    /// Add one to get the row numbers shown in the sheet. Returns `None` if nothing was written.
+    pub fn appended_rows(&self) -> Option<std::ops::Range<usize>> {
+        let updated_range = self.updates.as_ref()?.updated_range.as_ref()?;
+        let range = util::parse_a1_notation(updated_range)?;
+        Some(range.start_row?..range.end_row? + 1)
+    }
+}
+
+impl fmt::Display for AppendValuesResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.updates {
+            Some(updates) => write!(f, "{}", updates),
+            None => write!(f, "nothing appended"),
+        }
+    }
+}
+
+/// The response returned from clearing a range of values.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ClearValuesResponse {
+    /// The spreadsheet the updates were applied to.
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The range (in A1 notation) that was cleared.
+    /// If the requested range was unbounded, this is the actual range that was cleared, bounded to the sheet's limits.
+    #[serde(rename = "clearedRange")]
+    pub cleared_range: Option<String>,
+}
+
+/// The response returned from clearing several ranges at once.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchClearValuesResponse {
+    /// The spreadsheet the updates were applied to.
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The ranges (in A1 notation) that were cleared, bounded to the sheets' limits.
+    #[serde(rename = "clearedRanges", default)]
+    pub cleared_ranges: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BatchClearValuesRequest<'a> {
+    ranges: &'a [&'a str],
+}
+
+/// The parts of a spreadsheet resource this crate reads.
+#[derive(Deserialize)]
+struct Spreadsheet {
+    #[serde(default)]
+    properties: SpreadsheetProperties,
+    #[serde(default)]
+    sheets: Vec<Sheet>,
+}
+
+/// The parts of a sheet resource this crate reads.
+#[derive(Deserialize)]
+struct Sheet {
+    #[serde(default)]
+    properties: SheetProperties,
+}
+
+/// Properties of a spreadsheet.
+///
+/// See [Google Sheets Docs: `SpreadsheetProperties`]
+///
+/// [Google Sheets Docs: `SpreadsheetProperties`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets#spreadsheetproperties
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SpreadsheetProperties {
+    /// The title of the spreadsheet.
+    pub title: Option<String>,
+    /// The locale of the spreadsheet, such as `en_US`.
+    pub locale: Option<String>,
+    /// The time zone of the spreadsheet, in CLDR format such as `America/New_York`. Date and
+    /// time serial numbers are wall-clock times in this zone.
+    #[serde(rename = "timeZone")]
+    pub time_zone: Option<String>,
+}
+
+/// The response returned from reading several ranges at once.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchGetValuesResponse {
+    /// The ID of the spreadsheet the data was retrieved from.
+    #[serde(rename = "spreadsheetId")]
+    pub spreadsheet_id: Option<String>,
+    /// The requested values, one `ValueRange` per requested range, in the same order as the ranges were requested.
+    #[serde(rename = "valueRanges", default)]
+    pub value_ranges: Vec<ValueRange>,
+}
+
+/// The request body for writing several ranges with
+/// [`Sheets::batch_update`](Sheets::batch_update).
+///
+/// See [Google Sheets Docs: `spreadsheets.values.batchUpdate`]
+///
+/// [Google Sheets Docs: `spreadsheets.values.batchUpdate`]: https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets.values/batchUpdate#request-body
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchUpdateValuesRequest {
+    /// How the input data should be interpreted.
+    #[serde(rename = "valueInputOption")]
+    pub value_input_option: ValueInputOption,
+    /// The new values to apply to the spreadsheet, each with its own range.
+    pub data: Vec<ValueRange>,
+    /// Whether the response should include the values of the cells that were updated.
+    #[serde(
+        rename = "includeValuesInResponse",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub include_values_in_response: Option<bool>,
+    /// How values in the response should be rendered. Defaults to [`ValueRenderOption::FormattedValue`].
+    #[serde(
+        rename = "responseValueRenderOption",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub response_value_render_option: Option<ValueRenderOption>,
+    /// How dates in the response should be rendered. Defaults to [`DateTimeRenderOption::SerialNumber`].
+    #[serde(
+        rename = "responseDateTimeRenderOption",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub response_date_time_render_option: Option<DateTimeRenderOption>,
+}
+
+impl BatchUpdateValuesRequest {
+    /// Write `data` as if it were typed into the UI, without returning the written values.
+    pub fn new(data: Vec<ValueRange>) -> Self {
+        Self::with_options(data, &WriteOptions::default())
+    }
+
+    /// Write `data` according to `options`. The insert data option is not used by this endpoint.
+    pub fn with_options(data: Vec<ValueRange>, options: &WriteOptions) -> Self {
+        Self {
+            value_input_option: options.value_input_option,
+            data,
+            include_values_in_response: options.include_values_in_response,
+            response_value_render_option: options.response_value_render_option,
+            response_date_time_render_option: options.response_date_time_render_option,
+        }
+    }
+}
+
+impl ValueRange {
+    /// Values to write to `range`, one inner `Vec` per row.
+    pub fn new<T: Into<CellValue>>(range: &str, values: Vec<Vec<T>>) -> Self {
+        Self {
+            range: Some(String::from(range)),
+            values: Some(into_rows(values)),
+            major_dimension: Some(Dimension::ROWS),
+        }
+    }
+}
+
 /// The response returned from updating values.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct UpdateValuesResponse {
@@ -362,4 +1137,217 @@ pub struct BatchUpdateValuesResponse {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        into_rows, leftover_ranges, pad_rows, AppendValuesResponse, BatchClearValuesRequest,
+        BatchClearValuesResponse, BatchGetValuesResponse, BatchUpdateValuesRequest, CellValue,
+        ClearValuesResponse, DateTimeRenderOption, Dimension, GetValuesOptions, GridSize,
+        InsertDataOption, ValueInputOption, ValueRange, ValueRenderOption, WriteOptions,
+    };
+
+    #[test]
+    fn test_append_response_rows() {
+        let response: AppendValuesResponse = serde_json::from_value(json!({
+            "spreadsheetId": "abc123",
+            "tableRange": "'Q1 Sales'!A1:C41",
+            "updates": {
+                "spreadsheetId": "abc123",
+                "updatedRange": "'Q1 Sales'!A42:C44",
+                "updatedRows": 3,
+                "updatedColumns": 3,
+                "updatedCells": 9
+            }
+        }))
+        .unwrap();
+        assert_eq!(response.table_range.as_deref(), Some("'Q1 Sales'!A1:C41"));
+        assert_eq!(response.appended_rows(), Some(41..44));
+
+        let nothing: AppendValuesResponse =
+            serde_json::from_value(json!({"spreadsheetId": "abc123"})).unwrap();
+        assert_eq!(nothing.appended_rows(), None);
+    }
+
+    #[test]
+    fn test_clear_response() {
+        let response: ClearValuesResponse = serde_json::from_value(json!({
+            "spreadsheetId": "abc123",
+            "clearedRange": "Sheet1!A1:Z1000"
+        }))
+        .unwrap();
+        assert_eq!(response.cleared_range.as_deref(), Some("Sheet1!A1:Z1000"));
+    }
+
+    #[test]
+    fn test_batch_clear_request_and_response() {
+        let ranges = ["'Q1 Sales'!A2:D", "Log!A:A"];
+        assert_eq!(
+            serde_json::to_value(BatchClearValuesRequest { ranges: &ranges }).unwrap(),
+            json!({"ranges": ["'Q1 Sales'!A2:D", "Log!A:A"]})
+        );
+
+        let response: BatchClearValuesResponse = serde_json::from_value(json!({
+            "spreadsheetId": "abc123",
+            "clearedRanges": ["'Q1 Sales'!A2:D1000", "Log!A1:A1000"]
+        }))
+        .unwrap();
+        assert_eq!(response.cleared_ranges.len(), 2);
+    }
+
+    #[test]
+    fn test_pad_rows() {
+        let rows = pad_rows(into_rows(vec![vec!["a", "b", "c"], vec!["d"], vec![]]));
+        assert!(rows.iter().all(|row| row.len() == 3));
+        assert_eq!(rows[1][1], CellValue::Empty);
+    }
+
+    #[test]
+    fn test_leftover_ranges() {
+        let grid = GridSize {
+            rows: 1000,
+            columns: 26,
+        };
+        assert_eq!(
+            leftover_ranges("Q1 Sales", 3, 2, grid),
+            vec!["'Q1 Sales'!A4:Z1000", "'Q1 Sales'!C1:Z3"]
+        );
+        assert_eq!(
+            leftover_ranges("Log", 1, 1, grid),
+            vec!["'Log'!A2:Z1000", "'Log'!B1:Z1"]
+        );
+        assert_eq!(leftover_ranges("Log", 0, 0, grid), vec!["'Log'"]);
+        // the data fills the grid in one or both directions
+        assert_eq!(leftover_ranges("Log", 5, 26, grid), vec!["'Log'!A6:Z1000"]);
+        assert_eq!(
+            leftover_ranges("Log", 1000, 3, grid),
+            vec!["'Log'!D1:Z1000"]
+        );
+        assert!(leftover_ranges("Log", 1000, 26, grid).is_empty());
+    }
+
+    #[test]
+    fn test_write_options_query() {
+        assert_eq!(
+            WriteOptions::default().append_query(),
+            vec![
+                ("valueInputOption", "USER_ENTERED"),
+                ("insertDataOption", "INSERT_ROWS"),
+            ]
+        );
+        assert_eq!(
+            WriteOptions::default().update_query(),
+            vec![("valueInputOption", "USER_ENTERED")]
+        );
+
+        let options = WriteOptions {
+            insert_data_option: InsertDataOption::Overwrite,
+            include_values_in_response: Some(true),
+            response_date_time_render_option: Some(DateTimeRenderOption::FormattedString),
+            ..WriteOptions::raw()
+        };
+        assert_eq!(
+            options.append_query(),
+            vec![
+                ("valueInputOption", "RAW"),
+                ("insertDataOption", "OVERWRITE"),
+                ("includeValuesInResponse", "true"),
+                ("responseDateTimeRenderOption", "FORMATTED_STRING"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_update_request_body() {
+        let mut request = BatchUpdateValuesRequest::new(vec![
+            ValueRange::new("Sheet1!A1", vec![vec!["a", "b"]]),
+            ValueRange::new("Sheet2!A1", vec![vec![1, 2]]),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "valueInputOption": "USER_ENTERED",
+                "data": [
+                    {"range": "Sheet1!A1", "majorDimension": "ROWS", "values": [["a", "b"]]},
+                    {"range": "Sheet2!A1", "majorDimension": "ROWS", "values": [[1.0, 2.0]]}
+                ]
+            })
+        );
+
+        request.value_input_option = ValueInputOption::Raw;
+        request.include_values_in_response = Some(true);
+        request.response_value_render_option = Some(ValueRenderOption::Formula);
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["valueInputOption"], "RAW");
+        assert_eq!(body["includeValuesInResponse"], true);
+        assert_eq!(body["responseValueRenderOption"], "FORMULA");
+        assert!(body.get("responseDateTimeRenderOption").is_none());
+    }
+
+    #[test]
+    fn test_batch_get_response_keeps_request_order() {
+        let response: BatchGetValuesResponse = serde_json::from_str(
+            r#"{
+                "spreadsheetId": "abc123",
+                "valueRanges": [
+                    {"range": "Sheet2!A1:B1", "majorDimension": "ROWS", "values": [["a", "b"]]},
+                    {"range": "Sheet1!A1:A2", "majorDimension": "ROWS"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.value_ranges.len(), 2);
+        assert_eq!(
+            response.value_ranges[0].range.as_deref(),
+            Some("Sheet2!A1:B1")
+        );
+        assert!(response.value_ranges[1].values.is_none());
+
+        let empty: BatchGetValuesResponse =
+            serde_json::from_str(r#"{"spreadsheetId": "abc123"}"#).unwrap();
+        assert!(empty.value_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_get_values_options_query() {
+        assert!(GetValuesOptions::default().query().is_empty());
+
+        let options = GetValuesOptions {
+            major_dimension: Some(Dimension::COLUMNS),
+            value_render_option: Some(ValueRenderOption::UnformattedValue),
+            date_time_render_option: Some(DateTimeRenderOption::FormattedString),
+        };
+        assert_eq!(
+            options.query(),
+            vec![
+                ("majorDimension", "COLUMNS"),
+                ("valueRenderOption", "UNFORMATTED_VALUE"),
+                ("dateTimeRenderOption", "FORMATTED_STRING"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_options_match_serde_names() {
+        for option in &[
+            ValueRenderOption::FormattedValue,
+            ValueRenderOption::UnformattedValue,
+            ValueRenderOption::Formula,
+        ] {
+            assert_eq!(
+                serde_json::to_value(option).unwrap(),
+                serde_json::json!(option.as_str())
+            );
+        }
+        for option in &[
+            DateTimeRenderOption::SerialNumber,
+            DateTimeRenderOption::FormattedString,
+        ] {
+            assert_eq!(
+                serde_json::to_value(option).unwrap(),
+                serde_json::json!(option.as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// What happened during a single call to the Google Sheets API.
+///
+/// One event is reported per call, after any retries have finished.
+#[derive(Clone, Debug)]
+pub struct RequestEvent<'a> {
+    /// The API method that was called, for example `spreadsheets.values.append`.
+    pub endpoint: &'static str,
+    /// The spreadsheet the call was made against.
+    pub spreadsheet_id: &'a str,
+    /// The status of the final response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+    /// Time from the first attempt until the final response was read, including backoff.
+    pub latency: Duration,
+    /// Bytes in the request body, summed over every attempt.
+    pub bytes_sent: u64,
+    /// Bytes in the final response body.
+    pub bytes_received: u64,
+    /// How many times the call was retried after the first attempt.
+    pub retries: u32,
+}
+
+impl RequestEvent<'_> {
+    /// Whether the call ended with a successful response.
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|status| status.is_success())
+    }
+}
+
+/// Observes every call made through a [`SheetsClient`](crate::SheetsClient).
+///
+/// Register one with [`SheetsClientBuilder::observer`](crate::SheetsClientBuilder::observer) to
+/// forward API usage to a metrics system. Observers are called inline, so keep them quick.
+pub trait RequestObserver: Send + Sync {
+    fn on_request(&self, event: &RequestEvent<'_>);
+}
+
+/// Counters for calls to a single endpoint.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub errors: u64,
+    pub retries: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub total_latency: Duration,
+}
+
+impl EndpointStats {
+    fn record(&mut self, event: &RequestEvent<'_>) {
+        self.requests += 1;
+        self.errors += u64::from(!event.is_success());
+        self.retries += u64::from(event.retries);
+        self.bytes_sent += event.bytes_sent;
+        self.bytes_received += event.bytes_received;
+        self.total_latency += event.latency;
+    }
+}
+
+/// A snapshot of the counters kept by [`StatsCollector`].
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Stats {
+    /// Totals across every endpoint.
+    pub total: EndpointStats,
+    /// Counters per endpoint, keyed by endpoint name.
+    pub endpoints: HashMap<&'static str, EndpointStats>,
+    /// Counters per spreadsheet id.
+    pub spreadsheets: HashMap<String, EndpointStats>,
+}
+
+/// The built-in [`RequestObserver`] that keeps in-process counters.
+///
+/// Enable it with [`SheetsClientBuilder::collect_stats`](crate::SheetsClientBuilder::collect_stats)
+/// and read it with [`Sheets::stats`](crate::Sheets::stats).
+#[derive(Default, Debug)]
+pub struct StatsCollector {
+    breakdown: Mutex<Stats>,
+}
+
+impl StatsCollector {
+    /// The number of calls recorded so far, without taking a full snapshot.
+    pub fn requests(&self) -> u64 {
+        self.breakdown.lock().unwrap().total.requests
+    }
+
+    /// A copy of the current counters.
+    pub fn snapshot(&self) -> Stats {
+        self.breakdown.lock().unwrap().clone()
+    }
+
+    /// Reset every counter to zero.
+    pub fn reset(&self) {
+        *self.breakdown.lock().unwrap() = Stats::default();
+    }
+}
+
+impl RequestObserver for StatsCollector {
+    fn on_request(&self, event: &RequestEvent<'_>) {
+        let mut stats = self.breakdown.lock().unwrap();
+        stats.total.record(event);
+        stats
+            .endpoints
+            .entry(event.endpoint)
+            .or_default()
+            .record(event);
+        stats
+            .spreadsheets
+            .entry(event.spreadsheet_id.to_string())
+            .or_default()
+            .record(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use super::{RequestEvent, RequestObserver, StatsCollector};
+
+    fn event(
+        endpoint: &'static str,
+        status: Option<StatusCode>,
+        retries: u32,
+    ) -> RequestEvent<'static> {
+        RequestEvent {
+            endpoint,
+            spreadsheet_id: "abc123",
+            status,
+            latency: Duration::from_millis(100),
+            bytes_sent: 10,
+            bytes_received: 20,
+            retries,
+        }
+    }
+
+    #[test]
+    fn test_stats_collector_counts_per_endpoint() {
+        let stats = StatsCollector::default();
+        stats.on_request(&event("spreadsheets.values.get", Some(StatusCode::OK), 0));
+        stats.on_request(&event(
+            "spreadsheets.values.get",
+            Some(StatusCode::TOO_MANY_REQUESTS),
+            3,
+        ));
+        stats.on_request(&event("spreadsheets.values.append", None, 1));
+
+        let snapshot = stats.snapshot();
+        assert_eq!(stats.requests(), 3);
+        assert_eq!(snapshot.total.requests, 3);
+        assert_eq!(snapshot.total.errors, 2);
+        assert_eq!(snapshot.total.retries, 4);
+        assert_eq!(snapshot.total.bytes_sent, 30);
+        assert_eq!(snapshot.total.total_latency, Duration::from_millis(300));
+
+        let get = &snapshot.endpoints["spreadsheets.values.get"];
+        assert_eq!(get.requests, 2);
+        assert_eq!(get.errors, 1);
+        assert_eq!(snapshot.spreadsheets["abc123"].bytes_received, 60);
+    }
+
+    #[test]
+    fn test_stats_collector_reset() {
+        let stats = StatsCollector::default();
+        stats.on_request(&event("spreadsheets.values.get", Some(StatusCode::OK), 0));
+        stats.reset();
+        assert_eq!(stats.requests(), 0);
+        assert_eq!(stats.snapshot(), Default::default());
+    }
+}
@@ -0,0 +1,712 @@
+use serde::de::value::MapDeserializer;
+use serde::de::{DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde::ser::Error as _;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use snafu::ResultExt;
+
+use crate::util::{get_a1_notation, sheet_range};
+use crate::{
+    ApiError, AppendValuesResponse, BatchUpdateValuesRequest, BatchUpdateValuesResponse, CellValue,
+    Dimension, GetValuesOptions, Result, SerializeRecord, Sheets, ValueRange, ValueRenderOption,
+    WriteOptions,
+};
+
+/// How [`Sheets::read_records_with`] finds and reads records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadRecordsOptions {
+    /// The zero-indexed row holding the column names. Rows above it are ignored. Defaults to 0.
+    pub header_row: usize,
+    /// Whether rows without any values are left out, rather than read as records with every
+    /// field missing. Defaults to `true`.
+    pub skip_blank_rows: bool,
+    /// How values are read. Defaults to [`ValueRenderOption::UnformattedValue`], so numbers and
+    /// booleans arrive as such instead of as formatted text.
+    ///
+    /// Numbers and booleans still read into `String` fields, as they'd be written: `94107`,
+    /// `2.5`, `TRUE`. Read with [`ValueRenderOption::FormattedValue`] to get the text the sheet
+    /// shows instead, such as the leading zeros of a formatted zip code.
+    pub get: GetValuesOptions,
+}
+
+impl Default for ReadRecordsOptions {
+    fn default() -> Self {
+        Self {
+            header_row: 0,
+            skip_blank_rows: true,
+            get: GetValuesOptions {
+                value_render_option: Some(ValueRenderOption::UnformattedValue),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A cell as the JSON value serde sees when deserializing a record.
+///
+/// Whole numbers become integers so they can be read into integer fields.
+pub(crate) fn cell_to_json(cell: &CellValue) -> Value {
+    match cell {
+        CellValue::Empty => Value::Null,
+        CellValue::String(s) | CellValue::Formula(s) | CellValue::Error(s) => {
+            Value::String(s.clone())
+        }
+        CellValue::Number(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+            Value::Number(Number::from(*n as i64))
+        }
+        CellValue::Number(n) => Number::from_f64(*n).map_or(Value::Null, Value::Number),
+        CellValue::Bool(b) => Value::Bool(*b),
+    }
+}
+
+/// Deserializes a record from the JSON of its cells, reading numbers and booleans as their text
+/// where a string is expected, so numeric ids and zip codes fit `String` fields.
+struct CellDeserializer(Value);
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for CellDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for CellDeserializer {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::Object(object) => visitor.visit_map(MapDeserializer::new(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, CellDeserializer(value))),
+            )),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::Number(n) => visitor.visit_string(n.to_string()),
+            Value::Bool(b) => visitor.visit_string(CellValue::Bool(b).to_string()),
+            other => other.deserialize_string(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(CellDeserializer(other)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// A row as a JSON object keyed by column name.
+///
+/// Empty cells and columns without a name are left out, so they read as missing fields.
+pub(crate) fn row_to_object(headers: &[String], row: &[CellValue]) -> Map<String, Value> {
+    headers
+        .iter()
+        .zip(row)
+        .filter(|(header, cell)| !header.is_empty() && !cell.is_empty())
+        .fold(Map::new(), |mut object, (header, cell)| {
+            // with duplicate column names, the first one wins
+            object
+                .entry(header.clone())
+                .or_insert_with(|| cell_to_json(cell));
+            object
+        })
+}
+
+/// The column names in a header row, trimmed.
+pub(crate) fn header_names(row: &[CellValue]) -> Vec<String> {
+    row.iter()
+        .map(|cell| cell.to_string().trim().to_string())
+        .collect()
+}
+
+/// How [`Sheets::append_records_with`] and [`Sheets::write_records_with`] map fields to columns.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteRecordsOptions {
+    /// The zero-indexed row holding the column names. Defaults to 0.
+    pub header_row: usize,
+    /// Whether fields without a column are given one, added after the last named column.
+    pub create_missing_headers: bool,
+    /// Whether a field without a column fails with [`ApiError::UnknownField`] instead of being
+    /// left out. Has no effect with `create_missing_headers`.
+    pub strict: bool,
+    /// How the rows are written.
+    pub write: WriteOptions,
+}
+
+/// A cell holding a JSON value from a serialized record.
+///
+/// Arrays and objects are written as their JSON text.
+pub(crate) fn json_to_cell(value: &Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Empty,
+        Value::Bool(b) => CellValue::Bool(*b),
+        Value::Number(n) => n.as_f64().map_or(CellValue::Empty, CellValue::Number),
+        Value::String(s) => CellValue::from(s),
+        Value::Array(_) | Value::Object(_) => CellValue::String(value.to_string()),
+    }
+}
+
+/// Serializes each record into a JSON object keyed by field name.
+fn records_to_objects<T: Serialize>(records: &[T]) -> Result<Vec<Map<String, Value>>> {
+    records
+        .iter()
+        .map(
+            |record| match serde_json::to_value(record).context(SerializeRecord {})? {
+                Value::Object(object) => Ok(object),
+                _ => Err(serde_json::Error::custom(
+                    "records must serialize to a struct or map",
+                ))
+                .context(SerializeRecord {}),
+            },
+        )
+        .collect()
+}
+
+/// Makes sure every field has a column, per `options`.
+///
+/// Returns whether columns were added to `headers`.
+fn match_headers(
+    headers: &mut Vec<String>,
+    objects: &[Map<String, Value>],
+    options: &WriteRecordsOptions,
+) -> Result<bool> {
+    // new columns go after the last named one, not after trailing blank header cells
+    if options.create_missing_headers {
+        let named = headers
+            .iter()
+            .rposition(|h| !h.is_empty())
+            .map_or(0, |i| i + 1);
+        headers.truncate(named);
+    }
+    let mut added = false;
+
+    for field in objects.iter().flat_map(Map::keys) {
+        if headers.iter().any(|header| header == field) {
+            continue;
+        }
+        if options.create_missing_headers {
+            headers.push(field.clone());
+            added = true;
+        } else if options.strict {
+            return Err(ApiError::UnknownField {
+                field: field.clone(),
+            });
+        }
+    }
+    Ok(added)
+}
+
+/// A record's values, in the order of `headers`.
+///
+/// Columns without a matching field are left empty, and so are columns without a name.
+fn object_to_row(headers: &[String], object: &Map<String, Value>) -> Vec<CellValue> {
+    let mut row: Vec<CellValue> = headers
+        .iter()
+        .map(|header| match object.get(header) {
+            Some(value) if !header.is_empty() => json_to_cell(value),
+            _ => CellValue::Empty,
+        })
+        .collect();
+    while row.last().is_some_and(CellValue::is_empty) {
+        row.pop();
+    }
+    row
+}
+
+/// Deserializes every row after the header row into a `T`.
+fn parse_records<T: DeserializeOwned>(
+    mut rows: Vec<Vec<CellValue>>,
+    options: &ReadRecordsOptions,
+) -> Result<Vec<T>> {
+    if rows.len() <= options.header_row {
+        return Ok(Vec::new());
+    }
+    let records = rows.split_off(options.header_row + 1);
+    let headers = header_names(&rows[options.header_row]);
+
+    records
+        .into_iter()
+        .enumerate()
+        .filter(|(_, row)| !options.skip_blank_rows || !row.iter().all(CellValue::is_empty))
+        .map(|(offset, row)| {
+            let object = row_to_object(&headers, &row);
+            T::deserialize(CellDeserializer(Value::Object(object))).map_err(|source| {
+                ApiError::RecordError {
+                    row: options.header_row + offset + 2,
+                    source,
+                }
+            })
+        })
+        .collect()
+}
+
+impl Sheets {
+    /// Reads every row of a sheet (tab) into a `T`, using the first row as column names.
+    ///
+    /// See [`read_records_with`](Sheets::read_records_with).
+    pub async fn read_records<T: DeserializeOwned>(&self, sheet_title: &str) -> Result<Vec<T>> {
+        self.read_records_with(sheet_title, &ReadRecordsOptions::default())
+            .await
+    }
+
+    /// Reads the rows below a header row into `T`s, matching fields to columns by name.
+    ///
+    /// Each row is deserialized as a map from column name to value, so the order of the columns
+    /// doesn't matter and columns `T` doesn't know are ignored, unless `T` denies unknown fields.
+    /// Empty cells are missing fields: use `Option` or `#[serde(default)]` for columns that may
+    /// be blank. A row that can't be deserialized fails with [`ApiError::RecordError`], which
+    /// carries the row's number as shown in the sheet.
+    ///
+    /// Cells are read unformatted by default, so numbers arrive as numbers. A `String` field over
+    /// a numeric or checkbox cell, such as a column of ids, gets the value's text.
+    ///
+    /// ```no_run
+    /// use googlesheets::Sheets;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Order {
+    ///     #[serde(rename = "Order ID")]
+    ///     id: u32,
+    ///     #[serde(rename = "Customer")]
+    ///     customer: String,
+    ///     #[serde(rename = "Notes")]
+    ///     notes: Option<String>,
+    /// }
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let orders: Vec<Order> = sheets.read_records("Orders").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_records_with<T: DeserializeOwned>(
+        &self,
+        sheet_title: &str,
+        options: &ReadRecordsOptions,
+    ) -> Result<Vec<T>> {
+        let get = GetValuesOptions {
+            major_dimension: Some(Dimension::ROWS),
+            ..options.get
+        };
+        let values = self
+            .get_values_with(&sheet_range(sheet_title, None), &get)
+            .await?;
+
+        parse_records(values.values.unwrap_or_default(), options)
+    }
+
+    /// Appends records after the table below a header row, one row per record.
+    ///
+    /// See [`append_records_with`](Sheets::append_records_with).
+    pub async fn append_records<T: Serialize>(
+        &self,
+        sheet_title: &str,
+        records: &[T],
+    ) -> Result<AppendValuesResponse> {
+        self.append_records_with(sheet_title, records, &WriteRecordsOptions::default())
+            .await
+    }
+
+    /// Appends records after the table below a header row, putting each field in the column
+    /// named after it.
+    ///
+    /// The header row is read first, so fields land in the sheet's column order whatever order
+    /// `T` declares them in. Fields without a column are left out, rejected, or given a new
+    /// column, depending on `options`. Columns without a matching field are left empty.
+    pub async fn append_records_with<T: Serialize>(
+        &self,
+        sheet_title: &str,
+        records: &[T],
+        options: &WriteRecordsOptions,
+    ) -> Result<AppendValuesResponse> {
+        let (headers, rows) = self.records_to_rows(sheet_title, records, options).await?;
+
+        let table = sheet_range(
+            sheet_title,
+            Some(&get_a1_notation(
+                Some(0),
+                Some(options.header_row),
+                Some(headers.len().saturating_sub(1)),
+                None,
+            )),
+        );
+        self.append_rows_with(&table, rows, &options.write).await
+    }
+
+    /// Replaces the records below a header row, one row per record.
+    ///
+    /// See [`write_records_with`](Sheets::write_records_with).
+    pub async fn write_records<T: Serialize>(
+        &self,
+        sheet_title: &str,
+        records: &[T],
+    ) -> Result<BatchUpdateValuesResponse> {
+        self.write_records_with(sheet_title, records, &WriteRecordsOptions::default())
+            .await
+    }
+
+    /// Replaces the records below a header row, putting each field in the column named after it.
+    ///
+    /// Columns are matched as in [`append_records_with`](Sheets::append_records_with). The
+    /// records are written directly below the header row, and every row below the last record
+    /// is cleared, so the sheet ends up holding exactly `records`.
+    ///
+    /// ```no_run
+    /// use googlesheets::{Sheets, WriteRecordsOptions};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Task {
+    ///     #[serde(rename = "Title")]
+    ///     title: String,
+    ///     #[serde(rename = "Done")]
+    ///     done: bool,
+    /// }
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let tasks = vec![Task { title: String::from("Write docs"), done: false }];
+    /// let options = WriteRecordsOptions {
+    ///     create_missing_headers: true,
+    ///     ..Default::default()
+    /// };
+    /// sheets.write_records_with("Tasks", &tasks, &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_records_with<T: Serialize>(
+        &self,
+        sheet_title: &str,
+        records: &[T],
+        options: &WriteRecordsOptions,
+    ) -> Result<BatchUpdateValuesResponse> {
+        let (headers, mut rows) = self.records_to_rows(sheet_title, records, options).await?;
+        let first_row = options.header_row + 1;
+        let height = rows.len();
+
+        // fill every column, so stale values in rows being overwritten are cleared too
+        for row in &mut rows {
+            row.resize(headers.len(), CellValue::Empty);
+        }
+
+        let response = if rows.is_empty() {
+            BatchUpdateValuesResponse::default()
+        } else {
+            let data = vec![ValueRange::new(
+                &sheet_range(sheet_title, Some(&format!("A{}", first_row + 1))),
+                rows,
+            )];
+            self.batch_update(&BatchUpdateValuesRequest::with_options(
+                data,
+                &options.write,
+            ))
+            .await?
+        };
+
+        // the Values API rejects ranges past the grid, so the clear stops at its edges
+        let grid = self.grid_size(sheet_title).await?;
+        if let Some(below) = grid.rows_from(sheet_title, first_row + height) {
+            self.clear_range(&below).await?;
+        }
+
+        Ok(response)
+    }
+
+    /// Reads the header row and lays `records` out in its columns, writing the header row
+    /// back first if columns were added.
+    async fn records_to_rows<T: Serialize>(
+        &self,
+        sheet_title: &str,
+        records: &[T],
+        options: &WriteRecordsOptions,
+    ) -> Result<(Vec<String>, Vec<Vec<CellValue>>)> {
+        let objects = records_to_objects(records)?;
+
+        let header_range = sheet_range(
+            sheet_title,
+            Some(&get_a1_notation(
+                None,
+                Some(options.header_row),
+                None,
+                Some(options.header_row),
+            )),
+        );
+        let header_row = self
+            .get_values(&header_range)
+            .await?
+            .values
+            .and_then(|rows| rows.into_iter().next())
+            .unwrap_or_default();
+        let mut headers = header_names(&header_row);
+
+        if match_headers(&mut headers, &objects, options)? {
+            self.update_values_with(
+                &sheet_range(sheet_title, Some(&format!("A{}", options.header_row + 1))),
+                vec![headers.clone()],
+                &options.write,
+            )
+            .await?;
+        }
+
+        let rows = objects
+            .iter()
+            .map(|object| object_to_row(&headers, object))
+            .collect();
+        Ok((headers, rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{
+        cell_to_json, match_headers, object_to_row, parse_records, records_to_objects,
+        ReadRecordsOptions, WriteRecordsOptions,
+    };
+    use crate::{ApiError, CellValue};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Order {
+        #[serde(rename = "Order ID")]
+        id: u32,
+        #[serde(rename = "Customer")]
+        customer: String,
+        #[serde(rename = "Paid")]
+        paid: bool,
+        #[serde(rename = "Notes")]
+        notes: Option<String>,
+    }
+
+    fn rows(value: serde_json::Value) -> Vec<Vec<CellValue>> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_cell_to_json() {
+        assert_eq!(cell_to_json(&CellValue::Number(3.0)), json!(3));
+        assert_eq!(cell_to_json(&CellValue::Number(2.5)), json!(2.5));
+        assert_eq!(cell_to_json(&CellValue::Empty), json!(null));
+        assert_eq!(cell_to_json(&CellValue::from("x")), json!("x"));
+    }
+
+    #[test]
+    fn test_parse_records_by_column_name() {
+        let orders: Vec<Order> = parse_records(
+            rows(json!([
+                ["Customer", "Extra", "Order ID", "Paid", "Notes"],
+                ["Acme", "ignored", 1001, true],
+                [],
+                ["Globex", "", 1002, false, "rush"],
+            ])),
+            &ReadRecordsOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            orders,
+            vec![
+                Order {
+                    id: 1001,
+                    customer: String::from("Acme"),
+                    paid: true,
+                    notes: None,
+                },
+                Order {
+                    id: 1002,
+                    customer: String::from("Globex"),
+                    paid: false,
+                    notes: Some(String::from("rush")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_records_reads_numbers_into_strings() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Store {
+            #[serde(rename = "ID")]
+            id: String,
+            #[serde(rename = "Zip")]
+            zip: Option<String>,
+            #[serde(rename = "Open")]
+            open: String,
+            #[serde(rename = "Rating")]
+            rating: f64,
+        }
+
+        let stores: Vec<Store> = parse_records(
+            rows(json!([
+                ["ID", "Zip", "Open", "Rating"],
+                [1001, 94107, true, 4.5],
+                [7, null, false, 3]
+            ])),
+            &ReadRecordsOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            stores,
+            vec![
+                Store {
+                    id: String::from("1001"),
+                    zip: Some(String::from("94107")),
+                    open: String::from("TRUE"),
+                    rating: 4.5,
+                },
+                Store {
+                    id: String::from("7"),
+                    zip: None,
+                    open: String::from("FALSE"),
+                    rating: 3.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_records_header_row_and_blank_rows() {
+        let options = ReadRecordsOptions {
+            header_row: 1,
+            skip_blank_rows: false,
+            ..Default::default()
+        };
+        let records: Vec<std::collections::HashMap<String, String>> = parse_records(
+            rows(json!([["Report"], ["Name"], ["a"], [""], ["b"]])),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[1].is_empty());
+
+        let none: Vec<Order> = parse_records(Vec::new(), &options).unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_parse_records_reports_row_number() {
+        let error = parse_records::<Order>(
+            rows(json!([
+                ["Order ID", "Customer", "Paid"],
+                [1, "Acme", true],
+                [],
+                ["not a number", "Globex", false],
+            ])),
+            &ReadRecordsOptions::default(),
+        )
+        .unwrap_err();
+
+        match error {
+            ApiError::RecordError { row, .. } => assert_eq!(row, 4),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Task {
+        #[serde(rename = "Title")]
+        title: &'static str,
+        #[serde(rename = "Done")]
+        done: bool,
+        #[serde(rename = "Owner")]
+        owner: Option<&'static str>,
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| String::from(*name)).collect()
+    }
+
+    #[test]
+    fn test_object_to_row_follows_header_order() {
+        let objects = records_to_objects(&[Task {
+            title: "Write docs",
+            done: true,
+            owner: None,
+        }])
+        .unwrap();
+        let headers = strings(&["Done", "", "Title", "Owner", "Due"]);
+        assert_eq!(
+            object_to_row(&headers, &objects[0]),
+            vec![
+                CellValue::Bool(true),
+                CellValue::Empty,
+                CellValue::from("Write docs")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_headers() {
+        let objects = records_to_objects(&[Task {
+            title: "Write docs",
+            done: false,
+            owner: Some("sam"),
+        }])
+        .unwrap();
+
+        let mut headers = strings(&["Title", "Done", ""]);
+        assert!(!match_headers(&mut headers, &objects, &WriteRecordsOptions::default()).unwrap());
+        assert_eq!(headers, strings(&["Title", "Done", ""]));
+
+        let strict = WriteRecordsOptions {
+            strict: true,
+            ..Default::default()
+        };
+        match match_headers(&mut headers, &objects, &strict) {
+            Err(ApiError::UnknownField { field }) => assert_eq!(field, "Owner"),
+            other => panic!("unexpected result: {:?}", other.map_err(|e| e.to_string())),
+        }
+
+        let create = WriteRecordsOptions {
+            create_missing_headers: true,
+            ..Default::default()
+        };
+        assert!(match_headers(&mut headers, &objects, &create).unwrap());
+        assert_eq!(headers, strings(&["Title", "Done", "Owner"]));
+
+        let mut empty = Vec::new();
+        match_headers(&mut empty, &objects, &create).unwrap();
+        assert_eq!(empty, strings(&["Title", "Done", "Owner"]));
+    }
+
+    #[test]
+    fn test_records_must_be_objects() {
+        assert!(matches!(
+            records_to_objects(&[1, 2]),
+            Err(ApiError::SerializeRecord { .. })
+        ));
+    }
+}
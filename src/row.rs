@@ -0,0 +1,279 @@
+use crate::{ApiError, CellValue, Result};
+
+/// A type that maps to a row of a sheet, one field per column.
+///
+/// Implement it with `#[derive(SheetRow)]`, behind the `derive` feature. Fields fill columns
+/// from `A` in declaration order, and each field's column header is its name. Both can be
+/// changed per field:
+///
+/// - `#[sheet(column = "C")]` puts the field in column `C`; later fields continue from `D`.
+/// - `#[sheet(header = "Due date")]` names the column's header.
+/// - `#[sheet(skip)]` leaves the field out of the row. It's read back as `Default::default()`.
+/// - `#[sheet(with = "module")]` converts the field with `module::to_cell(&T) -> CellValue` and
+///   `module::from_cell(&CellValue) -> Result<T, String>`, for example to write a formula.
+///
+/// Other fields convert with [`ToCell`] and [`FromCell`].
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use googlesheets::{CellValue, SheetRow};
+///
+/// #[derive(SheetRow)]
+/// struct Task {
+///     #[sheet(header = "Title")]
+///     title: String,
+///     #[sheet(column = "C", header = "Due date")]
+///     due: Option<String>,
+///     #[sheet(skip)]
+///     cached: Vec<u8>,
+/// }
+///
+/// let row = Task { title: "Write docs".into(), due: None, cached: Vec::new() }.to_row();
+/// assert_eq!(row, vec![CellValue::from("Write docs"), CellValue::Empty, CellValue::Empty]);
+/// assert_eq!(Task::header_row()[2], CellValue::from("Due date"));
+/// # }
+/// ```
+pub trait SheetRow: Sized {
+    /// Every column a field maps to, in the order the fields are declared.
+    fn columns() -> Vec<SheetColumn>;
+
+    /// The row's values, each in its column. Columns without a field are empty.
+    fn to_row(&self) -> Vec<CellValue>;
+
+    /// Reads a row back. Missing cells read as empty.
+    ///
+    /// Fails with [`ApiError::InvalidCell`] on the first cell that doesn't convert.
+    fn from_row(row: &[CellValue]) -> Result<Self>;
+
+    /// A row holding each column's header in its column.
+    fn header_row() -> Vec<CellValue> {
+        let columns = Self::columns();
+        let mut row =
+            vec![CellValue::Empty; columns.iter().map(|c| c.index + 1).max().unwrap_or(0)];
+        for column in columns {
+            row[column.index] = CellValue::from(column.header);
+        }
+        row
+    }
+}
+
+/// Where a field of a [`SheetRow`] lives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SheetColumn {
+    /// The text of the column's header.
+    pub header: &'static str,
+    /// The zero-indexed column, so `0` is column `A`.
+    pub index: usize,
+}
+
+/// A value that can be written to a single cell.
+pub trait ToCell {
+    fn to_cell(&self) -> CellValue;
+}
+
+/// A value that can be read from a single cell.
+pub trait FromCell: Sized {
+    /// Fails with a message saying what was expected.
+    fn from_cell(cell: &CellValue) -> std::result::Result<Self, String>;
+}
+
+impl ToCell for CellValue {
+    fn to_cell(&self) -> CellValue {
+        self.clone()
+    }
+}
+
+impl FromCell for CellValue {
+    fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+        Ok(cell.clone())
+    }
+}
+
+impl ToCell for String {
+    fn to_cell(&self) -> CellValue {
+        CellValue::from(self)
+    }
+}
+
+impl ToCell for &str {
+    fn to_cell(&self) -> CellValue {
+        CellValue::from(*self)
+    }
+}
+
+impl FromCell for String {
+    /// Any cell, as it would be written; empty cells are empty strings.
+    fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+        Ok(cell.to_string())
+    }
+}
+
+impl ToCell for bool {
+    fn to_cell(&self) -> CellValue {
+        CellValue::Bool(*self)
+    }
+}
+
+impl FromCell for bool {
+    /// A checkbox, or the text `TRUE` or `FALSE` in any case.
+    fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+        match cell {
+            CellValue::Bool(b) => Ok(*b),
+            CellValue::String(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+            CellValue::String(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+            other => Err(format!("expected TRUE or FALSE, found {:?}", other)),
+        }
+    }
+}
+
+macro_rules! impl_cell_float {
+    ($($t:ty),*) => {
+        $(
+            impl ToCell for $t {
+                fn to_cell(&self) -> CellValue {
+                    CellValue::Number(*self as f64)
+                }
+            }
+
+            impl FromCell for $t {
+                /// A number, or text that parses as one.
+                fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+                    match cell {
+                        CellValue::Number(n) => Ok(*n as $t),
+                        CellValue::String(s) => s
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("expected a number, found {:?}", s)),
+                        other => Err(format!("expected a number, found {:?}", other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_cell_integer {
+    ($($t:ty),*) => {
+        $(
+            impl ToCell for $t {
+                fn to_cell(&self) -> CellValue {
+                    CellValue::Number(*self as f64)
+                }
+            }
+
+            impl FromCell for $t {
+                /// A whole number in range, or text that parses as one.
+                fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+                    match cell {
+                        CellValue::Number(n)
+                            if n.fract() == 0.0
+                                && *n >= <$t>::MIN as f64
+                                && *n <= <$t>::MAX as f64 =>
+                        {
+                            Ok(*n as $t)
+                        }
+                        CellValue::String(s) => s.trim().parse().map_err(|_| {
+                            format!("expected a whole number, found {:?}", s)
+                        }),
+                        other => Err(format!(
+                            "expected a whole number fitting {}, found {:?}",
+                            stringify!($t),
+                            other
+                        )),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_cell_float!(f64, f32);
+impl_cell_integer!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+
+impl<T: ToCell> ToCell for Option<T> {
+    fn to_cell(&self) -> CellValue {
+        self.as_ref().map_or(CellValue::Empty, ToCell::to_cell)
+    }
+}
+
+impl<T: FromCell> FromCell for Option<T> {
+    /// `None` for an empty cell.
+    fn from_cell(cell: &CellValue) -> std::result::Result<Self, String> {
+        if cell.is_empty() {
+            Ok(None)
+        } else {
+            T::from_cell(cell).map(Some)
+        }
+    }
+}
+
+/// Used by `#[derive(SheetRow)]`: converts the cell at `index` with `convert`.
+#[doc(hidden)]
+pub fn read_cell<T>(
+    row: &[CellValue],
+    index: usize,
+    header: &str,
+    convert: impl FnOnce(&CellValue) -> std::result::Result<T, String>,
+) -> Result<T> {
+    let empty = CellValue::Empty;
+    convert(row.get(index).unwrap_or(&empty)).map_err(|message| ApiError::InvalidCell {
+        column: String::from(header),
+        message,
+    })
+}
+
+/// Used by `#[derive(SheetRow)]`: places each cell at its index.
+#[doc(hidden)]
+pub fn build_row(cells: Vec<(usize, CellValue)>) -> Vec<CellValue> {
+    let width = cells.iter().map(|(index, _)| index + 1).max().unwrap_or(0);
+    let mut row = vec![CellValue::Empty; width];
+    for (index, cell) in cells {
+        row[index] = cell;
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_row, read_cell, FromCell, ToCell};
+    use crate::{ApiError, CellValue};
+
+    #[test]
+    fn test_numbers_from_cells() {
+        assert_eq!(u8::from_cell(&CellValue::Number(7.0)), Ok(7));
+        assert!(u8::from_cell(&CellValue::Number(300.0)).is_err());
+        assert!(i32::from_cell(&CellValue::Number(1.5)).is_err());
+        assert_eq!(i32::from_cell(&CellValue::from(" 42 ")), Ok(42));
+        assert_eq!(f64::from_cell(&CellValue::Number(1.5)), Ok(1.5));
+        assert!(f64::from_cell(&CellValue::Bool(true)).is_err());
+    }
+
+    #[test]
+    fn test_options_and_bools() {
+        assert_eq!(Option::<u32>::from_cell(&CellValue::Empty), Ok(None));
+        assert_eq!(
+            Option::<u32>::from_cell(&CellValue::Number(3.0)),
+            Ok(Some(3))
+        );
+        assert_eq!(None::<u32>.to_cell(), CellValue::Empty);
+        assert_eq!(bool::from_cell(&CellValue::from("True")), Ok(true));
+        assert!(bool::from_cell(&CellValue::Empty).is_err());
+        assert_eq!(
+            String::from_cell(&CellValue::Number(2.0)),
+            Ok(String::from("2"))
+        );
+    }
+
+    #[test]
+    fn test_row_helpers() {
+        let row = build_row(vec![(2, 1.to_cell()), (0, "a".to_cell())]);
+        assert_eq!(row, vec!["a".into(), CellValue::Empty, 1.into()]);
+
+        assert_eq!(read_cell(&row, 5, "Notes", String::from_cell).unwrap(), "");
+        match read_cell(&row, 0, "Count", u32::from_cell) {
+            Err(ApiError::InvalidCell { column, .. }) => assert_eq!(column, "Count"),
+            other => panic!("unexpected result: {:?}", other.map_err(|e| e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Conversions between `chrono` dates and times and the serial numbers sheets store them as.
+//!
+//! Read with [`ValueRenderOption::UnformattedValue`](crate::ValueRenderOption::UnformattedValue),
+//! a date or time cell holds the number of days since the epoch `1899-12-30`, with the time of
+//! day as the fraction: `45292.75` is `2024-01-01 18:00`. Serial numbers carry no time zone;
+//! they're wall-clock times in the spreadsheet's time zone, which
+//! [`Sheets::properties`](crate::Sheets::properties) returns.
+//!
+//! Written serial numbers only show as dates in cells with a date or time number format.
+//!
+//! `NaiveDate` and `NaiveDateTime` convert directly, as cells and in typed rows. Zoned
+//! `DateTime`s need the spreadsheet's time zone as well, which a row can't know, so they go
+//! through [`datetime_in`], whose functions take it. With the `chrono-tz` feature,
+//! `Sheets::time_zone` fetches it as a `chrono_tz::Tz`.
+//!
+//! ```rust
+//! use chrono::NaiveDate;
+//! use googlesheets::serial;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+//! assert_eq!(serial::from_date(date), 45292.0);
+//! assert_eq!(serial::to_date(45292.75), Some(date));
+//! ```
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serializer};
+
+#[cfg(feature = "chrono-tz")]
+use crate::{ApiError, Result as ApiResult, Sheets};
+use crate::{CellValue, FromCell, ToCell};
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Day zero of serial numbers.
+fn epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1899, 12, 30)
+        .unwrap()
+        .and_time(NaiveTime::MIN)
+}
+
+/// The serial number of midnight on `date`.
+pub fn from_date(date: NaiveDate) -> f64 {
+    from_datetime(date.and_time(NaiveTime::MIN))
+}
+
+/// The serial number of a wall-clock time, to the millisecond.
+pub fn from_datetime(datetime: NaiveDateTime) -> f64 {
+    (datetime - epoch()).num_milliseconds() as f64 / MILLIS_PER_DAY
+}
+
+/// The serial number of an instant, as wall-clock time in the spreadsheet's time zone `tz`.
+pub fn from_datetime_in<Tz: TimeZone, Sheet: TimeZone>(datetime: &DateTime<Tz>, tz: &Sheet) -> f64 {
+    from_datetime(datetime.with_timezone(tz).naive_local())
+}
+
+/// The date a serial number falls on, ignoring the time of day.
+///
+/// `None` if the serial number is out of `chrono`'s range, or not a number.
+pub fn to_date(serial: f64) -> Option<NaiveDate> {
+    to_datetime(serial.floor()).map(|datetime| datetime.date())
+}
+
+/// The wall-clock time of a serial number, rounded to the millisecond.
+///
+/// `None` if the serial number is out of `chrono`'s range, or not a number.
+pub fn to_datetime(serial: f64) -> Option<NaiveDateTime> {
+    let millis = (serial * MILLIS_PER_DAY).round();
+    if !millis.is_finite() || millis.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    epoch().checked_add_signed(Duration::milliseconds(millis as i64))
+}
+
+/// The instant of a serial number read as wall-clock time in the spreadsheet's time zone `tz`.
+///
+/// Times skipped by a daylight saving change don't exist and return `None`; times repeated by
+/// one resolve to the earlier instant.
+pub fn to_datetime_in<Tz: TimeZone>(serial: f64, tz: &Tz) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&to_datetime(serial)?).earliest()
+}
+
+#[cfg(feature = "chrono-tz")]
+impl Sheets {
+    /// The spreadsheet's time zone, for use with [`to_datetime_in`] and [`from_datetime_in`].
+    ///
+    /// Fails with [`ApiError::InvalidTimeZone`] if the spreadsheet has no time zone, or one
+    /// `chrono-tz` doesn't know. Needs the `chrono-tz` feature.
+    pub async fn time_zone(&self) -> ApiResult<chrono_tz::Tz> {
+        let name = self.properties().await?.time_zone.unwrap_or_default();
+        name.parse().map_err(|_| ApiError::InvalidTimeZone { name })
+    }
+}
+
+/// A date or time cell's serial number. Also accepts text in ISO 8601 form, which is how
+/// cells read with the default [`FormattedValue`](crate::ValueRenderOption::FormattedValue)
+/// rendering look if they're formatted that way.
+fn cell_serial(cell: &CellValue) -> Result<f64, String> {
+    match cell {
+        CellValue::Number(n) => Ok(*n),
+        CellValue::String(s) => s
+            .parse::<NaiveDateTime>()
+            .map(from_datetime)
+            .or_else(|_| s.parse::<NaiveDate>().map(from_date))
+            .map_err(|_| format!("expected a date serial number, found {:?}", s)),
+        other => Err(format!("expected a date serial number, found {:?}", other)),
+    }
+}
+
+impl From<NaiveDate> for CellValue {
+    fn from(date: NaiveDate) -> Self {
+        CellValue::Number(from_date(date))
+    }
+}
+
+impl From<NaiveDateTime> for CellValue {
+    fn from(datetime: NaiveDateTime) -> Self {
+        CellValue::Number(from_datetime(datetime))
+    }
+}
+
+impl ToCell for NaiveDate {
+    fn to_cell(&self) -> CellValue {
+        CellValue::from(*self)
+    }
+}
+
+impl FromCell for NaiveDate {
+    fn from_cell(cell: &CellValue) -> Result<Self, String> {
+        let serial = cell_serial(cell)?;
+        to_date(serial).ok_or_else(|| format!("serial number {} is out of range", serial))
+    }
+}
+
+impl ToCell for NaiveDateTime {
+    fn to_cell(&self) -> CellValue {
+        CellValue::from(*self)
+    }
+}
+
+impl FromCell for NaiveDateTime {
+    fn from_cell(cell: &CellValue) -> Result<Self, String> {
+        let serial = cell_serial(cell)?;
+        to_datetime(serial).ok_or_else(|| format!("serial number {} is out of range", serial))
+    }
+}
+
+/// Reads and writes a `NaiveDate` as a serial number with serde, for
+/// [`Sheets::read_records`](crate::Sheets::read_records) and
+/// [`Sheets::write_records`](crate::Sheets::write_records):
+/// `#[serde(with = "googlesheets::serial::date")]`.
+pub mod date {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(from_date(*date))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let cell = CellValue::deserialize(deserializer)?;
+        NaiveDate::from_cell(&cell).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Reads and writes a `NaiveDateTime` as a serial number with serde:
+/// `#[serde(with = "googlesheets::serial::datetime")]`.
+pub mod datetime {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        datetime: &NaiveDateTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(from_datetime(*datetime))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<NaiveDateTime, D::Error> {
+        let cell = CellValue::deserialize(deserializer)?;
+        NaiveDateTime::from_cell(&cell).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts zoned `DateTime`s to and from cells, given the spreadsheet's time zone.
+///
+/// For typed rows, wrap them in a module of your own for `#[sheet(with = ...)]`:
+///
+/// ```rust
+/// mod in_new_york {
+///     use chrono::{DateTime, FixedOffset, Utc};
+///     use googlesheets::{serial, CellValue};
+///
+///     fn sheet_tz() -> FixedOffset {
+///         FixedOffset::west_opt(5 * 3600).unwrap()
+///     }
+///
+///     pub fn to_cell(datetime: &DateTime<Utc>) -> CellValue {
+///         serial::datetime_in::to_cell(datetime, &sheet_tz())
+///     }
+///
+///     pub fn from_cell(cell: &CellValue) -> Result<DateTime<Utc>, String> {
+///         serial::datetime_in::from_cell(cell, &sheet_tz()).map(|datetime| datetime.into())
+///     }
+/// }
+///
+/// use chrono::{TimeZone, Utc};
+/// use googlesheets::CellValue;
+///
+/// let instant = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap();
+/// assert_eq!(in_new_york::to_cell(&instant), CellValue::Number(45292.75));
+/// assert_eq!(in_new_york::from_cell(&CellValue::Number(45292.75)), Ok(instant));
+/// ```
+pub mod datetime_in {
+    use super::*;
+
+    /// The serial number of an instant, as wall-clock time in the spreadsheet's time zone `tz`.
+    pub fn to_cell<Tz: TimeZone, Sheet: TimeZone>(
+        datetime: &DateTime<Tz>,
+        tz: &Sheet,
+    ) -> CellValue {
+        CellValue::Number(from_datetime_in(datetime, tz))
+    }
+
+    /// The instant of a date or time cell read as wall-clock time in the spreadsheet's time
+    /// zone `tz`. Accepts the same cells as `NaiveDateTime`'s [`FromCell`].
+    pub fn from_cell<Tz: TimeZone>(cell: &CellValue, tz: &Tz) -> Result<DateTime<Tz>, String> {
+        let serial = cell_serial(cell)?;
+        to_datetime_in(serial, tz).ok_or_else(|| {
+            format!(
+                "serial number {} is out of range, or skipped in the time zone",
+                serial
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(from_date(date(1899, 12, 30)), 0.0);
+        assert_eq!(from_date(date(1900, 3, 1)), 61.0);
+        assert_eq!(from_date(date(1899, 12, 29)), -1.0);
+        assert_eq!(to_date(45292.0), Some(date(2024, 1, 1)));
+        assert_eq!(to_date(45292.999), Some(date(2024, 1, 1)));
+        assert_eq!(to_date(f64::NAN), None);
+        assert_eq!(to_date(1e300), None);
+    }
+
+    #[test]
+    fn test_datetimes() {
+        let evening = date(2024, 1, 1).and_hms_opt(18, 0, 0).unwrap();
+        assert_eq!(from_datetime(evening), 45292.75);
+        assert_eq!(to_datetime(45292.75), Some(evening));
+
+        // float noise rounds away
+        let noon = date(2024, 1, 1).and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(to_datetime(45292.5 + 1e-10), Some(noon));
+    }
+
+    #[test]
+    fn test_time_zones() {
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        let instant = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap();
+        assert_eq!(from_datetime_in(&instant, &new_york), 45292.75);
+
+        let read = to_datetime_in(45292.75, &new_york).unwrap();
+        assert_eq!(read, instant);
+    }
+
+    #[test]
+    fn test_zoned_cells() {
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        let instant = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap();
+        assert_eq!(
+            datetime_in::to_cell(&instant, &new_york),
+            CellValue::Number(45292.75)
+        );
+        assert_eq!(
+            datetime_in::from_cell(&CellValue::from("2024-01-01T18:00:00"), &new_york),
+            Ok(instant.with_timezone(&new_york))
+        );
+        assert!(datetime_in::from_cell(&CellValue::Empty, &Utc).is_err());
+    }
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(
+            CellValue::from(date(2024, 1, 1)),
+            CellValue::Number(45292.0)
+        );
+        assert_eq!(
+            NaiveDate::from_cell(&CellValue::Number(45292.0)),
+            Ok(date(2024, 1, 1))
+        );
+        assert_eq!(
+            NaiveDate::from_cell(&CellValue::from("2024-01-01")),
+            Ok(date(2024, 1, 1))
+        );
+        assert!(NaiveDate::from_cell(&CellValue::from("January")).is_err());
+        assert!(NaiveDateTime::from_cell(&CellValue::Empty).is_err());
+    }
+
+    #[test]
+    fn test_serde_modules() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Event {
+            #[serde(with = "crate::serial::date")]
+            day: NaiveDate,
+            #[serde(with = "crate::serial::datetime")]
+            starts: NaiveDateTime,
+        }
+
+        let event = Event {
+            day: date(2024, 1, 1),
+            starts: date(2024, 1, 1).and_hms_opt(18, 0, 0).unwrap(),
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value, json!({"day": 45292.0, "starts": 45292.75}));
+        assert_eq!(
+            serde_json::from_value::<Event>(json!({"day": 45292, "starts": 45292.75})).unwrap(),
+            event
+        );
+    }
+}
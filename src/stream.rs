@@ -0,0 +1,441 @@
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::cell::into_rows;
+use crate::chunk::{block_range, span};
+use crate::util::{get_a1_notation, parse_a1_notation, sheet_range, A1Range};
+use crate::{
+    ApiError, BatchUpdateValuesRequest, CellValue, Dimension, GetValuesOptions, Result, Sheets,
+    ValueRange, WriteOptions,
+};
+
+/// How [`Sheets::write_stream_with`] and [`Sheets::append_stream_with`] batch their rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamWriteOptions {
+    /// How many rows are buffered before they're sent. Defaults to 1,000.
+    pub batch_rows: usize,
+    /// How each batch is written.
+    pub write: WriteOptions,
+}
+
+impl Default for StreamWriteOptions {
+    fn default() -> Self {
+        Self {
+            batch_rows: 1000,
+            write: WriteOptions::default(),
+        }
+    }
+}
+
+/// What a streaming write wrote.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct StreamWriteSummary {
+    /// The number of rows written.
+    pub rows: usize,
+    /// The number of cells written, counting every value in every row.
+    pub cells: usize,
+    /// The number of batches the rows were sent in.
+    pub batches: usize,
+    /// The range the rows ended up in, such as `'Export'!A2:F120001`, if anything was written.
+    pub range: Option<String>,
+}
+
+impl StreamWriteSummary {
+    fn add(&mut self, rows: &[Vec<CellValue>]) {
+        self.rows += rows.len();
+        self.cells += rows.iter().map(Vec::len).sum::<usize>();
+        self.batches += 1;
+    }
+}
+
+/// Where the next batch of a [`Sheets::write_stream`] goes.
+struct Cursor {
+    anchor: A1Range,
+    offset: usize,
+}
+
+impl Cursor {
+    /// Fails unless `start` names a starting cell, such as `Export!A2` or `'Q1 Sales'!B2:F`.
+    fn new(start: &str) -> Result<Self> {
+        parse_a1_notation(start)
+            .filter(|range| range.start_column.is_some() && range.start_row.is_some())
+            .map(|anchor| Self { anchor, offset: 0 })
+            .ok_or_else(|| ApiError::InvalidRange {
+                range: String::from(start),
+            })
+    }
+
+    /// The rows placed directly below the previous batch.
+    fn next(&mut self, rows: Vec<Vec<CellValue>>) -> ValueRange {
+        let offset = self.offset;
+        self.offset += rows.len();
+        block_range(&self.anchor, offset, rows)
+    }
+}
+
+impl Sheets {
+    /// Writes rows from `rows` into consecutive ranges, starting at the cell `start` names.
+    ///
+    /// See [`write_stream_with`](Sheets::write_stream_with).
+    pub async fn write_stream<S, T>(&self, start: &str, rows: S) -> Result<StreamWriteSummary>
+    where
+        S: Stream<Item = Vec<T>>,
+        T: Into<CellValue>,
+    {
+        self.write_stream_with(start, rows, &StreamWriteOptions::default())
+            .await
+    }
+
+    /// Writes rows from `rows` into consecutive ranges, starting at the cell `start` names, such
+    /// as `'Export'!A2`.
+    ///
+    /// Rows are buffered into batches of `options.batch_rows`, each written directly below the
+    /// last. The stream isn't polled while a batch is being sent, so a slow sheet slows the
+    /// producer down instead of piling rows up in memory. Fails if `start` has no starting cell,
+    /// or on the first batch that fails to write; batches before it stay written.
+    ///
+    /// ```no_run
+    /// use futures::stream;
+    /// use googlesheets::Sheets;
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let rows = stream::iter((1..=1_000_000).map(|n| vec![n, n * n]));
+    /// let summary = sheets.write_stream("'Squares'!A2", rows).await?;
+    /// assert_eq!(summary.rows, 1_000_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_stream_with<S, T>(
+        &self,
+        start: &str,
+        rows: S,
+        options: &StreamWriteOptions,
+    ) -> Result<StreamWriteSummary>
+    where
+        S: Stream<Item = Vec<T>>,
+        T: Into<CellValue>,
+    {
+        let mut cursor = Cursor::new(start)?;
+        let mut summary = StreamWriteSummary::default();
+        let mut ranges = Vec::new();
+
+        let batches = rows.chunks(options.batch_rows.max(1));
+        futures::pin_mut!(batches);
+        while let Some(batch) = batches.next().await {
+            let batch = into_rows(batch);
+            summary.add(&batch);
+
+            let value_range = cursor.next(batch);
+            ranges.extend(value_range.range.clone());
+            let request = BatchUpdateValuesRequest::with_options(vec![value_range], &options.write);
+            self.batch_update(&request).await?;
+        }
+
+        summary.range = span(ranges.iter().map(String::as_str));
+        Ok(summary)
+    }
+
+    /// Appends rows from `rows` after the table found in a sheet or range.
+    ///
+    /// See [`append_stream_with`](Sheets::append_stream_with).
+    pub async fn append_stream<S, T>(
+        &self,
+        sheet_title_or_range: &str,
+        rows: S,
+    ) -> Result<StreamWriteSummary>
+    where
+        S: Stream<Item = Vec<T>>,
+        T: Into<CellValue>,
+    {
+        self.append_stream_with(sheet_title_or_range, rows, &StreamWriteOptions::default())
+            .await
+    }
+
+    /// Appends rows from `rows` after the table found in a sheet or range, as with
+    /// [`append_rows`](Sheets::append_rows).
+    ///
+    /// Rows are buffered into batches of `options.batch_rows` and each batch is appended in its
+    /// own request. As with [`write_stream_with`](Sheets::write_stream_with), the stream isn't
+    /// polled while a batch is being sent.
+    pub async fn append_stream_with<S, T>(
+        &self,
+        sheet_title_or_range: &str,
+        rows: S,
+        options: &StreamWriteOptions,
+    ) -> Result<StreamWriteSummary>
+    where
+        S: Stream<Item = Vec<T>>,
+        T: Into<CellValue>,
+    {
+        let mut summary = StreamWriteSummary::default();
+        let mut ranges = Vec::new();
+
+        let batches = rows.chunks(options.batch_rows.max(1));
+        futures::pin_mut!(batches);
+        while let Some(batch) = batches.next().await {
+            let batch = into_rows(batch);
+            summary.add(&batch);
+
+            let response = self
+                .append_rows_with(sheet_title_or_range, batch, &options.write)
+                .await?;
+            ranges.extend(response.updates.and_then(|updates| updates.updated_range));
+        }
+
+        summary.range = span(ranges.iter().map(String::as_str));
+        Ok(summary)
+    }
+}
+
+/// How [`Sheets::read_stream_with`] pages through a sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamReadOptions {
+    /// How many rows are fetched per request. Defaults to 1,000.
+    pub window_rows: usize,
+    /// The zero-indexed row to start reading from, for example to resume an earlier read.
+    pub start_row: usize,
+    /// How values are read. The major dimension is always [`Dimension::ROWS`].
+    pub get: GetValuesOptions,
+}
+
+impl Default for StreamReadOptions {
+    fn default() -> Self {
+        Self {
+            window_rows: 1000,
+            start_row: 0,
+            get: GetValuesOptions::default(),
+        }
+    }
+}
+
+/// The rows of a sheet, fetched one window at a time.
+struct Windows {
+    sheet_title: String,
+    window_rows: usize,
+    next_row: usize,
+    /// How many rows the sheet's grid has, once known. No window reaches past it.
+    grid_rows: Option<usize>,
+    /// Empty rows at the end of the last window, only yielded if more rows follow them.
+    pending_empty: usize,
+}
+
+impl Windows {
+    fn new(sheet_title: &str, options: &StreamReadOptions) -> Self {
+        Self {
+            sheet_title: String::from(sheet_title),
+            window_rows: options.window_rows.max(1),
+            next_row: options.start_row,
+            grid_rows: None,
+            pending_empty: 0,
+        }
+    }
+
+    /// The row after the current window, cut short at the end of the grid.
+    fn window_end(&self) -> usize {
+        let end = self.next_row + self.window_rows;
+        self.grid_rows.map_or(end, |rows| end.min(rows))
+    }
+
+    /// Every cell in the next window of rows, such as `'Export'!1001:2000`, or `None` once the
+    /// grid has been read to its end.
+    fn range(&self) -> Option<String> {
+        let end = self.window_end();
+        if self.next_row >= end {
+            return None;
+        }
+        let a1 = get_a1_notation(None, Some(self.next_row), None, Some(end - 1));
+        Some(sheet_range(&self.sheet_title, Some(&a1)))
+    }
+
+    /// Numbers the rows read from the current window and moves on to the next.
+    ///
+    /// The API leaves off empty rows at the end of a range, so an empty window means the sheet
+    /// has ended, and returns `None`.
+    fn advance(&mut self, rows: Vec<Vec<CellValue>>) -> Option<Vec<(usize, Vec<CellValue>)>> {
+        if rows.is_empty() {
+            return None;
+        }
+
+        let end = self.window_end();
+        let window_rows = end - self.next_row;
+        let first = self.next_row - self.pending_empty;
+        let gap = std::iter::repeat_with(Vec::new).take(self.pending_empty);
+        self.pending_empty = window_rows - rows.len().min(window_rows);
+        self.next_row = end;
+
+        Some((first..).zip(gap.chain(rows)).collect())
+    }
+}
+
+impl Sheets {
+    /// Reads every row of a sheet (tab) as a stream, a window of rows at a time.
+    ///
+    /// See [`read_stream_with`](Sheets::read_stream_with).
+    pub fn read_stream(
+        &self,
+        sheet_title: &str,
+    ) -> impl Stream<Item = Result<(usize, Vec<CellValue>)>> {
+        self.read_stream_with(sheet_title, &StreamReadOptions::default())
+    }
+
+    /// Reads the rows of a sheet (tab) as a stream, each paired with its zero-indexed row number.
+    ///
+    /// Nothing is fetched until the stream is polled. Rows are then requested
+    /// `options.window_rows` at a time, and the next window is only fetched once the previous
+    /// one has been consumed. The stream ends at the first window without any values, or at the
+    /// end of the sheet's grid, whose size is fetched first. Empty rows
+    /// between filled ones are yielded as empty `Vec`s, so row numbers always line up with the
+    /// sheet. To resume after an error, read again with `start_row` set to the row after the
+    /// last one received.
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use googlesheets::{Sheets, StreamReadOptions};
+    ///
+    /// # async fn run(sheets: Sheets) -> Result<(), googlesheets::ApiError> {
+    /// let options = StreamReadOptions {
+    ///     window_rows: 5000,
+    ///     start_row: 1,
+    ///     ..Default::default()
+    /// };
+    /// let mut rows = Box::pin(sheets.read_stream_with("Export", &options));
+    /// while let Some((index, row)) = rows.try_next().await? {
+    ///     println!("row {}: {} cells", index + 1, row.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_stream_with(
+        &self,
+        sheet_title: &str,
+        options: &StreamReadOptions,
+    ) -> impl Stream<Item = Result<(usize, Vec<CellValue>)>> {
+        let get = GetValuesOptions {
+            major_dimension: Some(Dimension::ROWS),
+            ..options.get
+        };
+        let state = (
+            self.clone(),
+            Windows::new(sheet_title, options),
+            VecDeque::new(),
+        );
+
+        stream::try_unfold(state, move |(sheets, mut windows, mut buffer)| async move {
+            if windows.grid_rows.is_none() {
+                let grid = sheets.grid_size(&windows.sheet_title).await?;
+                windows.grid_rows = Some(grid.rows);
+            }
+            while buffer.is_empty() {
+                let range = match windows.range() {
+                    Some(range) => range,
+                    None => return Ok(None),
+                };
+                let values = sheets.get_values_with(&range, &get).await?;
+                match windows.advance(values.values.unwrap_or_default()) {
+                    Some(rows) => buffer.extend(rows),
+                    None => return Ok(None),
+                }
+            }
+            let row = buffer.pop_front();
+            Ok(row.map(|row| (row, (sheets, windows, buffer))))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, StreamReadOptions, StreamWriteSummary, Windows};
+    use crate::cell::into_rows;
+    use crate::chunk::span;
+    use crate::CellValue;
+
+    #[test]
+    fn test_cursor_places_batches_below_each_other() {
+        let mut cursor = Cursor::new("'Q1 Sales'!B2:D").unwrap();
+        let first = cursor.next(into_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+        let second = cursor.next(into_rows(vec![vec![7, 8]]));
+        assert_eq!(first.range.as_deref(), Some("'Q1 Sales'!B2:D3"));
+        assert_eq!(second.range.as_deref(), Some("'Q1 Sales'!B4:C4"));
+        assert_eq!(
+            span(
+                [first.range.unwrap(), second.range.unwrap()]
+                    .iter()
+                    .map(String::as_str)
+            )
+            .as_deref(),
+            Some("'Q1 Sales'!B2:D4")
+        );
+    }
+
+    #[test]
+    fn test_cursor_needs_a_starting_cell() {
+        assert!(Cursor::new("Export!A2").is_ok());
+        assert!(Cursor::new("'Export'").is_err());
+        assert!(Cursor::new("Export!A:C").is_err());
+    }
+
+    #[test]
+    fn test_summary_counts() {
+        let mut summary = StreamWriteSummary::default();
+        summary.add(&into_rows(vec![vec!["a", "b"], vec!["c"]]));
+        summary.add(&into_rows(vec![vec!["d", "e", "f"]]));
+        assert_eq!(summary.rows, 3);
+        assert_eq!(summary.cells, 6);
+        assert_eq!(summary.batches, 2);
+    }
+
+    #[test]
+    fn test_windows_ranges() {
+        let options = StreamReadOptions {
+            window_rows: 100,
+            start_row: 250,
+            ..Default::default()
+        };
+        let mut windows = Windows::new("Q1 Sales", &options);
+        assert_eq!(windows.range().as_deref(), Some("'Q1 Sales'!251:350"));
+        windows.advance(into_rows(vec![vec!["a"]; 100]));
+        assert_eq!(windows.range().as_deref(), Some("'Q1 Sales'!351:450"));
+
+        let default = Windows::new("Export", &StreamReadOptions::default());
+        assert_eq!(default.range().as_deref(), Some("'Export'!1:1000"));
+    }
+
+    #[test]
+    fn test_windows_stop_at_the_grid() {
+        let mut windows = Windows::new("Export", &StreamReadOptions::default());
+        windows.grid_rows = Some(1500);
+        assert_eq!(windows.range().as_deref(), Some("'Export'!1:1000"));
+
+        // the last window is cut short, and its empty rows are counted against its real size
+        windows.advance(into_rows(vec![vec!["a"]; 1000])).unwrap();
+        assert_eq!(windows.range().as_deref(), Some("'Export'!1001:1500"));
+        windows.advance(into_rows(vec![vec!["b"]; 400])).unwrap();
+        assert_eq!(windows.pending_empty, 100);
+        assert_eq!(windows.range(), None);
+
+        let mut full = Windows::new("Export", &StreamReadOptions::default());
+        full.grid_rows = Some(1000);
+        full.advance(into_rows(vec![vec!["a"]; 400])).unwrap();
+        assert_eq!(full.range(), None);
+    }
+
+    #[test]
+    fn test_windows_number_rows_across_gaps() {
+        let options = StreamReadOptions {
+            window_rows: 3,
+            ..Default::default()
+        };
+        let mut windows = Windows::new("Export", &options);
+
+        let first = windows.advance(into_rows(vec![vec!["a"], vec![]])).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[1], (1, vec![]));
+
+        // the trailing empty row of the first window comes out once more rows follow it
+        let second = windows.advance(into_rows(vec![vec!["b"]])).unwrap();
+        assert_eq!(second, vec![(2, vec![]), (3, vec![CellValue::from("b")])]);
+
+        assert!(windows.advance(Vec::new()).is_none());
+    }
+}
@@ -39,7 +39,7 @@ const ASCII_UPPER: [char; 26] = [
 fn get_column_notation(column: usize) -> String {
     // A - Z
     if column < 26 {
-        return format!("{}", ASCII_UPPER[column]);
+        format!("{}", ASCII_UPPER[column])
     }
     // AA - ZZ
     else if column < 702 {
@@ -125,9 +125,224 @@ pub fn get_a1_notation(
     }
 }
 
+/// The inverse of [`get_column_notation`]: the zero-indexed number of a column like `"A"` or `"CF"`.
+///
+/// Returns `None` if `column` is empty or contains anything but ASCII letters.
+pub(crate) fn parse_column_notation(column: &str) -> Option<usize> {
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    column
+        .chars()
+        .map(|c| c.to_ascii_uppercase() as usize - 'A' as usize + 1)
+        .try_fold(0usize, |acc, digit| acc.checked_mul(26)?.checked_add(digit))
+        .map(|number| number - 1)
+}
+
+/// A range in A1 notation broken into its parts, with zero-indexed rows and columns.
+///
+/// Any part missing from the notation is `None`, so `A:B` has no rows and `2:4` has no columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct A1Range {
+    /// The title of the sheet the range is on, unquoted.
+    pub sheet_title: Option<String>,
+    pub start_column: Option<usize>,
+    pub start_row: Option<usize>,
+    pub end_column: Option<usize>,
+    pub end_row: Option<usize>,
+}
+
+/// The last column a sheet can have, `ZZZ`.
+pub(crate) const LAST_COLUMN: usize = 18277;
+
+/// Splits a cell reference like `B12` into its zero-indexed column and row.
+fn parse_cell(cell: &str) -> Option<(Option<usize>, Option<usize>)> {
+    let cell = cell.replace('$', "");
+    let split = cell
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(cell.len());
+    let (letters, digits) = cell.split_at(split);
+
+    let column = match letters {
+        "" => None,
+        // anything past `ZZZ` is a named range, such as `Sales2024`, rather than a cell
+        letters => Some(parse_column_notation(letters).filter(|&c| c <= LAST_COLUMN)?),
+    };
+    let row = match digits {
+        "" => None,
+        digits => Some(digits.parse::<usize>().ok()?.checked_sub(1)?),
+    };
+
+    if column.is_none() && row.is_none() {
+        None
+    } else {
+        Some((column, row))
+    }
+}
+
+/// Parses a range in A1 notation, such as `'Q1 Sales'!A2:D10`, as returned by the API.
+///
+/// This is the inverse of [`get_a1_notation`] and [`sheet_range`]. Returns `None` if the range
+/// can't be parsed.
+///
+/// # Examples
+///
+/// ```rust
+/// use googlesheets::util::parse_a1_notation;
+///
+/// let range = parse_a1_notation("'Q1 Sales'!A2:D10").unwrap();
+/// assert_eq!(range.sheet_title.as_deref(), Some("Q1 Sales"));
+/// assert_eq!((range.start_row, range.end_row), (Some(1), Some(9)));
+/// assert_eq!((range.start_column, range.end_column), (Some(0), Some(3)));
+/// ```
+pub fn parse_a1_notation(range: &str) -> Option<A1Range> {
+    // the sheet title is everything before the last `!`; quotes inside it are doubled
+    let (sheet_title, cells) = match range.rfind('!') {
+        Some(bang) => {
+            let title = &range[..bang];
+            let title = match title.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+                Some(quoted) => quoted.replace("''", "'"),
+                None => String::from(title),
+            };
+            (Some(title), &range[bang + 1..])
+        }
+        None => (None, range),
+    };
+
+    let mut parts = cells.splitn(2, ':');
+    let (start_column, start_row) = parse_cell(parts.next()?)?;
+    let (end_column, end_row) = match parts.next() {
+        Some(end) => parse_cell(end)?,
+        // a single cell is a range that starts and ends on it
+        None => (start_column, start_row),
+    };
+
+    Some(A1Range {
+        sheet_title,
+        start_column,
+        start_row,
+        end_column,
+        end_row,
+    })
+}
+
+/// Qualifies a range with the title of the sheet (tab) it's on, quoting the title so that
+/// spaces, quotes and other punctuation are safe.
+///
+/// With no range, the result refers to the whole sheet.
+///
+/// # Examples
+///
+/// ```rust
+/// use googlesheets::util::sheet_range;
+///
+/// assert_eq!(sheet_range("Q1 Sales", Some("A1:B2")), "'Q1 Sales'!A1:B2");
+/// assert_eq!(sheet_range("Bob's", None), "'Bob''s'");
+/// ```
+pub fn sheet_range(sheet_title: &str, range: Option<&str>) -> String {
+    let quoted = format!("'{}'", sheet_title.replace('\'', "''"));
+    match range {
+        Some(range) => format!("{}!{}", quoted, range),
+        None => quoted,
+    }
+}
+
+/// Turns either a sheet title or a range that names its sheet into a range.
+///
+/// Anything containing `!` is taken to be a range like `'Q1 Sales'!A1:D1` and returned as-is;
+/// anything else is taken to be the title of a sheet and refers to the whole sheet.
+pub(crate) fn sheet_or_range(sheet_title_or_range: &str) -> String {
+    if sheet_title_or_range.contains('!') {
+        String::from(sheet_title_or_range)
+    } else {
+        sheet_range(sheet_title_or_range, None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_a1_notation, get_column_notation};
+    use super::{
+        get_a1_notation, get_column_notation, parse_a1_notation, parse_column_notation,
+        sheet_or_range, sheet_range, A1Range,
+    };
+
+    #[test]
+    fn test_parse_column_notation_inverts_get_column_notation() {
+        for column in &[0, 3, 25, 26, 27, 52, 701, 702, 1567, 18277] {
+            assert_eq!(
+                parse_column_notation(&get_column_notation(*column)),
+                Some(*column)
+            );
+        }
+        assert_eq!(parse_column_notation("ab"), Some(27));
+        assert_eq!(parse_column_notation(""), None);
+        assert_eq!(parse_column_notation("A1"), None);
+    }
+
+    #[test]
+    fn test_parse_a1_notation() {
+        assert_eq!(
+            parse_a1_notation("Sheet1!A1:D5"),
+            Some(A1Range {
+                sheet_title: Some(String::from("Sheet1")),
+                start_column: Some(0),
+                start_row: Some(0),
+                end_column: Some(3),
+                end_row: Some(4),
+            })
+        );
+        assert_eq!(
+            parse_a1_notation("B3"),
+            Some(A1Range {
+                sheet_title: None,
+                start_column: Some(1),
+                start_row: Some(2),
+                end_column: Some(1),
+                end_row: Some(2),
+            })
+        );
+
+        let columns = parse_a1_notation("'It''s #1'!A:C").unwrap();
+        assert_eq!(columns.sheet_title.as_deref(), Some("It's #1"));
+        assert_eq!((columns.start_row, columns.end_row), (None, None));
+        assert_eq!(
+            (columns.start_column, columns.end_column),
+            (Some(0), Some(2))
+        );
+
+        let rows = parse_a1_notation("10:18").unwrap();
+        assert_eq!((rows.start_row, rows.end_row), (Some(9), Some(17)));
+        assert_eq!(rows.start_column, None);
+
+        let open_ended = parse_a1_notation("$A$5:A").unwrap();
+        assert_eq!((open_ended.start_row, open_ended.end_row), (Some(4), None));
+    }
+
+    #[test]
+    fn test_parse_a1_notation_invalid() {
+        assert_eq!(parse_a1_notation(""), None);
+        assert_eq!(parse_a1_notation("Sheet1!"), None);
+        assert_eq!(parse_a1_notation("A0"), None);
+        assert_eq!(parse_a1_notation("1A"), None);
+        assert_eq!(parse_a1_notation("Sales2024"), None);
+        assert_eq!(parse_a1_notation("Sheet1!AAAA1"), None);
+    }
+
+    #[test]
+    fn test_sheet_range_quotes_titles() {
+        assert_eq!(sheet_range("Sheet1", Some("A1")), "'Sheet1'!A1");
+        assert_eq!(sheet_range("Q1 Sales", None), "'Q1 Sales'");
+        assert_eq!(sheet_range("It's #1", Some("A:C")), "'It''s #1'!A:C");
+        assert_eq!(sheet_range("Données", Some("1:1")), "'Données'!1:1");
+    }
+
+    #[test]
+    fn test_sheet_or_range() {
+        assert_eq!(sheet_or_range("Log"), "'Log'");
+        assert_eq!(sheet_or_range("Q1 Sales"), "'Q1 Sales'");
+        assert_eq!(sheet_or_range("'Q1 Sales'!A1:D1"), "'Q1 Sales'!A1:D1");
+        assert_eq!(sheet_or_range("Log!A:C"), "Log!A:C");
+    }
 
     #[test]
     fn it_works() {